@@ -0,0 +1,110 @@
+use crate::{
+    DlssError, DlssSdk,
+    super_resolution::{DlssSuperResolution, DlssSuperResolutionSettings},
+};
+use std::{
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+use wgpu::{Device, Queue};
+
+/// Creates [`DlssSuperResolution`] contexts on a background thread, superseding obsolete requests.
+///
+/// At most one creation runs at a time, as NGX cannot cancel a creation once started. If settings change again while
+/// a creation is in flight, e.g. while dragging a settings slider, only the most recent settings are created next, and
+/// the result of the obsolete creation is discarded.
+pub struct DlssSuperResolutionCreator {
+    sdk: Arc<Mutex<DlssSdk>>,
+    device: Device,
+    queue: Queue,
+    in_flight: Option<InFlightCreation>,
+    next: Option<DlssSuperResolutionSettings>,
+}
+
+struct InFlightCreation {
+    settings: DlssSuperResolutionSettings,
+    canceled: bool,
+    thread: JoinHandle<Result<DlssSuperResolution, DlssError>>,
+}
+
+impl DlssSuperResolutionCreator {
+    /// Create a new [`DlssSuperResolutionCreator`], with no creation in flight.
+    pub fn new(sdk: Arc<Mutex<DlssSdk>>, device: &Device, queue: &Queue) -> Self {
+        Self {
+            sdk,
+            device: device.clone(),
+            queue: queue.clone(),
+            in_flight: None,
+            next: None,
+        }
+    }
+
+    /// Request a context with `settings`, superseding any previous request that has not completed.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn request(&mut self, settings: DlssSuperResolutionSettings) {
+        match &mut self.in_flight {
+            Some(in_flight) if in_flight.settings == settings => {
+                in_flight.canceled = false;
+                self.next = None;
+            }
+            Some(_) => self.next = Some(settings),
+            None => self.start(settings),
+        }
+    }
+
+    /// Discard all pending requests. A creation that is already running completes in the background, and its result is dropped.
+    pub fn cancel(&mut self) {
+        if let Some(in_flight) = &mut self.in_flight {
+            in_flight.canceled = true;
+        }
+        self.next = None;
+    }
+
+    /// Call once per frame. Returns the context for the most recent request once it has been created.
+    pub fn poll(&mut self) -> Option<Result<DlssSuperResolution, DlssError>> {
+        let in_flight = self
+            .in_flight
+            .take_if(|in_flight| in_flight.thread.is_finished())?;
+        let result = in_flight
+            .thread
+            .join()
+            .unwrap_or(Err(DlssError::SdkPoisoned));
+
+        if let Some(settings) = self.next.take() {
+            // Drop the obsolete context before creating its replacement, to avoid holding both in VRAM
+            drop(result);
+            self.start(settings);
+            return None;
+        }
+
+        (!in_flight.canceled).then_some(result)
+    }
+
+    /// Whether a creation is in flight or queued.
+    pub fn is_pending(&self) -> bool {
+        self.in_flight.is_some() || self.next.is_some()
+    }
+
+    fn start(&mut self, settings: DlssSuperResolutionSettings) {
+        let (sdk, device, queue) = (
+            Arc::clone(&self.sdk),
+            self.device.clone(),
+            self.queue.clone(),
+        );
+        self.in_flight = Some(InFlightCreation {
+            settings,
+            canceled: false,
+            thread: thread::spawn(move || {
+                DlssSuperResolution::new(
+                    settings.upscaled_resolution,
+                    settings.perf_quality_mode,
+                    settings.feature_flags,
+                    sdk,
+                    &device,
+                    &queue,
+                )
+            }),
+        });
+    }
+}
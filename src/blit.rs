@@ -0,0 +1,150 @@
+use std::{borrow::Cow, collections::HashMap};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Color, ColorTargetState, ColorWrites,
+    CommandEncoder, Device, FragmentState, LoadOp, MultisampleState, Operations,
+    PipelineCompilationOptions, PipelineLayout, PipelineLayoutDescriptor, PrimitiveState,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
+    ShaderModule, ShaderModuleDescriptor, ShaderSource, ShaderStages, StoreOp, TextureFormat,
+    TextureSampleType, TextureView, TextureViewDimension, VertexState,
+};
+
+/// Copies the DLSS output texture to a surface texture, which typically can't be written to by DLSS directly
+/// because it lacks [`wgpu::TextureUsages::STORAGE_BINDING`].
+///
+/// Any float source format is supported. If the source and target sizes differ, the source is point sampled.
+///
+/// Values are written as-is to sRGB and float targets. For `Rgba8Unorm`, `Bgra8Unorm`, and `Rgb10a2Unorm` targets,
+/// linear values are sRGB-encoded by the shader, as surfaces with those formats expect sRGB-encoded values.
+pub struct DlssOutputBlit {
+    device: Device,
+    bind_group_layout: BindGroupLayout,
+    pipeline_layout: PipelineLayout,
+    shader: ShaderModule,
+    pipelines: HashMap<TextureFormat, RenderPipeline>,
+}
+
+impl DlssOutputBlit {
+    /// Create a new [`DlssOutputBlit`]. Pipelines are created lazily for each target format.
+    pub fn new(device: &Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("dlss_output_blit_bind_group_layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("dlss_output_blit_pipeline_layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("dlss_output_blit_shader"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("blit.wgsl"))),
+        });
+
+        Self {
+            device: device.clone(),
+            bind_group_layout,
+            pipeline_layout,
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Encode a render pass into `command_encoder` that copies `source` to the whole of `target`.
+    ///
+    /// `target` must have been created with [`wgpu::TextureUsages::RENDER_ATTACHMENT`], and `source` with [`wgpu::TextureUsages::TEXTURE_BINDING`].
+    pub fn blit(
+        &mut self,
+        source: &TextureView,
+        target: &TextureView,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let target_format = target.texture().format();
+        let pipeline = self.pipelines.entry(target_format).or_insert_with(|| {
+            create_pipeline(
+                &self.device,
+                &self.pipeline_layout,
+                &self.shader,
+                target_format,
+            )
+        });
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("dlss_output_blit_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::TextureView(source),
+            }],
+        });
+
+        let mut pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("dlss_output_blit"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::BLACK),
+                    store: StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+fn create_pipeline(
+    device: &Device,
+    layout: &PipelineLayout,
+    shader: &ShaderModule,
+    target_format: TextureFormat,
+) -> RenderPipeline {
+    let encode_srgb = matches!(
+        target_format,
+        TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm | TextureFormat::Rgb10a2Unorm
+    );
+
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("dlss_output_blit_pipeline"),
+        layout: Some(layout),
+        vertex: VertexState {
+            module: shader,
+            entry_point: Some("vertex"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(FragmentState {
+            module: shader,
+            entry_point: Some("fragment"),
+            compilation_options: PipelineCompilationOptions {
+                constants: &[("ENCODE_SRGB", if encode_srgb { 1.0 } else { 0.0 })],
+                ..Default::default()
+            },
+            targets: &[Some(ColorTargetState {
+                format: target_format,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            })],
+        }),
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        multiview_mask: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,29 @@
+override ENCODE_SRGB: bool = false;
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vertex(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Fullscreen triangle
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return VertexOutput(vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0), uv);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let source_size = textureDimensions(source);
+    let coordinates = min(vec2<u32>(in.uv * vec2<f32>(source_size)), source_size - 1u);
+    var color = textureLoad(source, coordinates, 0);
+
+    if ENCODE_SRGB {
+        let rgb = saturate(color.rgb);
+        color = vec4<f32>(select(1.055 * pow(rgb, vec3<f32>(1.0 / 2.4)) - 0.055, rgb * 12.92, rgb <= vec3<f32>(0.0031308)), color.a);
+    }
+
+    return color;
+}
@@ -0,0 +1,84 @@
+/// Detects camera cuts, such as teleports and scene loads, by comparing the camera matrices of consecutive frames.
+///
+/// Use the result of [`Self::update`] as the `reset` render parameter when the engine has no explicit camera cut events.
+///
+/// Matrices are column-major.
+#[derive(Clone, Debug)]
+pub struct DlssCameraCutDetector {
+    /// Camera movement, in world units, above which a frame is treated as a cut.
+    pub translation_threshold: f32,
+    /// Camera rotation, in radians, above which a frame is treated as a cut.
+    pub rotation_threshold: f32,
+    /// Relative change in any projection matrix element above which a frame is treated as a cut, e.g. a sudden FOV change.
+    ///
+    /// Jitter is ignored.
+    pub projection_threshold: f32,
+    previous: Option<([f32; 16], [f32; 16])>,
+}
+
+impl Default for DlssCameraCutDetector {
+    fn default() -> Self {
+        Self {
+            translation_threshold: 5.0,
+            rotation_threshold: 45f32.to_radians(),
+            projection_threshold: 0.1,
+            previous: None,
+        }
+    }
+}
+
+impl DlssCameraCutDetector {
+    /// Call once per frame with the camera's view-to-world (camera transform) and view-to-clip (projection) matrices.
+    ///
+    /// Returns true if DLSS history should be reset, including on the first frame.
+    pub fn update(&mut self, view_to_world: [f32; 16], view_to_clip: [f32; 16]) -> bool {
+        let Some((previous_view_to_world, previous_view_to_clip)) =
+            self.previous.replace((view_to_world, view_to_clip))
+        else {
+            return true;
+        };
+
+        let translation = (0..3)
+            .map(|row| (view_to_world[12 + row] - previous_view_to_world[12 + row]).powi(2))
+            .sum::<f32>()
+            .sqrt();
+
+        // Angle of the relative rotation, from the trace of previous_rotation^T * rotation
+        let trace = (0..3)
+            .map(|column| {
+                (0..3)
+                    .map(|row| {
+                        view_to_world[column * 4 + row] * previous_view_to_world[column * 4 + row]
+                    })
+                    .sum::<f32>()
+                    / (column_length(&view_to_world, column)
+                        * column_length(&previous_view_to_world, column))
+                    .max(f32::EPSILON)
+            })
+            .sum::<f32>();
+        let rotation = ((trace - 1.0) / 2.0).clamp(-1.0, 1.0).acos();
+
+        // Elements 8 and 9 hold the jitter for perspective projections, and 12 and 13 for orthographic ones
+        let projection_changed = (0..16).filter(|&i| !matches!(i, 8 | 9 | 12 | 13)).any(|i| {
+            let (current, previous) = (view_to_clip[i], previous_view_to_clip[i]);
+            (current - previous).abs()
+                > self.projection_threshold * current.abs().max(previous.abs()).max(f32::EPSILON)
+        });
+
+        translation > self.translation_threshold
+            || rotation > self.rotation_threshold
+            || projection_changed
+    }
+
+    /// Forget the previous frame, so that the next call to [`Self::update`] reports a cut.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+fn column_length(matrix: &[f32; 16], column: usize) -> f32 {
+    (0..3)
+        .map(|row| matrix[column * 4 + row].powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
@@ -0,0 +1,31 @@
+use crate::DlssError;
+use wgpu::{CommandBuffer, CommandEncoder, CommandEncoderDescriptor, Device, PollType, Queue};
+
+/// Capture a single DLSS evaluation in an attached graphics debugger such as RenderDoc, for debugging a bad frame.
+///
+/// `render` should encode DLSS into the given command encoder, e.g. using [`crate::super_resolution::DlssSuperResolution::render`],
+/// and return the DLSS command buffer. Both are submitted to `queue`, and the capture is stopped once the GPU has finished.
+/// Work needed to produce the DLSS inputs should already have been submitted.
+///
+/// Does nothing beyond rendering if no graphics debugger is attached.
+///
+/// # Safety
+/// No other capture may be active. See [`Device::start_graphics_debugger_capture`].
+pub unsafe fn capture_evaluation(
+    device: &Device,
+    queue: &Queue,
+    render: impl FnOnce(&mut CommandEncoder) -> Result<CommandBuffer, DlssError>,
+) -> Result<(), DlssError> {
+    unsafe { device.start_graphics_debugger_capture() };
+
+    let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("dlss_capture"),
+    });
+    let result = render(&mut command_encoder).map(|dlss_command_buffer| {
+        queue.submit([command_encoder.finish(), dlss_command_buffer]);
+        let _ = device.poll(PollType::wait_indefinitely());
+    });
+
+    unsafe { device.stop_graphics_debugger_capture() };
+    result
+}
@@ -0,0 +1,160 @@
+use std::{borrow::Cow, collections::HashMap};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, BlendState, ColorTargetState, ColorWrites,
+    CommandEncoder, Device, FragmentState, LoadOp, MultisampleState, Operations,
+    PipelineCompilationOptions, PipelineLayout, PipelineLayoutDescriptor, PrimitiveState,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
+    ShaderModule, ShaderModuleDescriptor, ShaderSource, ShaderStages, StoreOp, TextureFormat,
+    TextureSampleType, TextureView, TextureViewDimension, VertexState,
+};
+
+/// How the color channels of a UI texture relate to its alpha channel.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UiAlphaMode {
+    /// Color channels have already been multiplied by alpha.
+    Premultiplied,
+    /// Color channels have not been multiplied by alpha.
+    Straight,
+}
+
+/// Blends a UI or overlay texture over the upscaled output, since UI should be rendered at the output resolution rather than upscaled by DLSS.
+///
+/// Typically used on the surface texture, after [`crate::blit::DlssOutputBlit`].
+///
+/// Any float UI format is supported. If the UI and target sizes differ, the UI is point sampled.
+/// Blending happens in the target's color space, so UI values should be encoded the same way as the target's contents.
+pub struct DlssUiComposite {
+    device: Device,
+    bind_group_layout: BindGroupLayout,
+    pipeline_layout: PipelineLayout,
+    shader: ShaderModule,
+    pipelines: HashMap<(TextureFormat, UiAlphaMode), RenderPipeline>,
+}
+
+impl DlssUiComposite {
+    /// Create a new [`DlssUiComposite`]. Pipelines are created lazily for each target format and alpha mode.
+    pub fn new(device: &Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("dlss_ui_composite_bind_group_layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("dlss_ui_composite_pipeline_layout"),
+            bind_group_layouts: &[Some(&bind_group_layout)],
+            immediate_size: 0,
+        });
+
+        // Reuses the blit shader, without sRGB encoding
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("dlss_ui_composite_shader"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("blit.wgsl"))),
+        });
+
+        Self {
+            device: device.clone(),
+            bind_group_layout,
+            pipeline_layout,
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Encode a render pass into `command_encoder` that blends `ui` over the existing contents of `target`.
+    ///
+    /// `target` must have been created with [`wgpu::TextureUsages::RENDER_ATTACHMENT`], and `ui` with [`wgpu::TextureUsages::TEXTURE_BINDING`].
+    pub fn composite(
+        &mut self,
+        ui: &TextureView,
+        ui_alpha_mode: UiAlphaMode,
+        target: &TextureView,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let target_format = target.texture().format();
+        let pipeline = self
+            .pipelines
+            .entry((target_format, ui_alpha_mode))
+            .or_insert_with(|| {
+                create_pipeline(
+                    &self.device,
+                    &self.pipeline_layout,
+                    &self.shader,
+                    target_format,
+                    ui_alpha_mode,
+                )
+            });
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("dlss_ui_composite_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::TextureView(ui),
+            }],
+        });
+
+        let mut pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("dlss_ui_composite"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+fn create_pipeline(
+    device: &Device,
+    layout: &PipelineLayout,
+    shader: &ShaderModule,
+    target_format: TextureFormat,
+    ui_alpha_mode: UiAlphaMode,
+) -> RenderPipeline {
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("dlss_ui_composite_pipeline"),
+        layout: Some(layout),
+        vertex: VertexState {
+            module: shader,
+            entry_point: Some("vertex"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(FragmentState {
+            module: shader,
+            entry_point: Some("fragment"),
+            compilation_options: PipelineCompilationOptions::default(),
+            targets: &[Some(ColorTargetState {
+                format: target_format,
+                blend: Some(match ui_alpha_mode {
+                    UiAlphaMode::Premultiplied => BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+                    UiAlphaMode::Straight => BlendState::ALPHA_BLENDING,
+                }),
+                write_mask: ColorWrites::ALL,
+            })],
+        }),
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        multiview_mask: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,199 @@
+use crate::{
+    DlssCounters, DlssError, DlssFeatureFlags, DlssPerfQualityMode, DlssSdk,
+    ray_reconstruction::{
+        DlssRayReconstruction, DlssRayReconstructionDepthMode, DlssRayReconstructionRoughnessMode,
+        DlssRayReconstructionSettings,
+    },
+    super_resolution::{DlssSuperResolution, DlssSuperResolutionSettings},
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+use wgpu::{Device, Queue};
+
+/// Owns a [`DlssSdk`] and caches DLSS contexts per camera, recreating them when their settings change or the SDK is reinitialized.
+///
+/// `K` identifies a camera, e.g. an entity ID.
+///
+/// Call [`Self::evict_unused`] once per frame to drop the contexts of cameras that were not rendered with DLSS since the previous call.
+pub struct DlssContextManager<K> {
+    sdk: Arc<Mutex<DlssSdk>>,
+    super_resolution: HashMap<K, CachedContext<DlssSuperResolution, DlssSuperResolutionSettings>>,
+    ray_reconstruction:
+        HashMap<K, CachedContext<DlssRayReconstruction, DlssRayReconstructionSettings>>,
+}
+
+impl<K: Hash + Eq> DlssContextManager<K> {
+    /// Create a new [`DlssContextManager`] with no cached contexts.
+    pub fn new(sdk: Arc<Mutex<DlssSdk>>) -> Self {
+        Self {
+            sdk,
+            super_resolution: HashMap::new(),
+            ray_reconstruction: HashMap::new(),
+        }
+    }
+
+    /// The SDK the contexts are created from.
+    pub fn sdk(&self) -> &Arc<Mutex<DlssSdk>> {
+        &self.sdk
+    }
+
+    /// Get the [`DlssSuperResolution`] context for `camera`, creating it if it does not exist or if the settings have changed.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn super_resolution(
+        &mut self,
+        camera: K,
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<&mut DlssSuperResolution, DlssError> {
+        let settings = DlssSuperResolutionSettings {
+            upscaled_resolution,
+            perf_quality_mode,
+            feature_flags,
+        };
+
+        let sdk_generation = self.sdk_generation()?;
+        get_or_create(
+            &mut self.super_resolution,
+            camera,
+            settings,
+            sdk_generation,
+            |context| &mut context.counters,
+            || {
+                DlssSuperResolution::new(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    Arc::clone(&self.sdk),
+                    device,
+                    queue,
+                )
+            },
+        )
+    }
+
+    /// Get the [`DlssRayReconstruction`] context for `camera`, creating it if it does not exist or if the settings have changed.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::ray_reconstruction_supported`] is true.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ray_reconstruction(
+        &mut self,
+        camera: K,
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        roughness_mode: DlssRayReconstructionRoughnessMode,
+        depth_mode: DlssRayReconstructionDepthMode,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<&mut DlssRayReconstruction, DlssError> {
+        let settings = DlssRayReconstructionSettings {
+            upscaled_resolution,
+            perf_quality_mode,
+            feature_flags,
+            roughness_mode,
+            depth_mode,
+        };
+
+        let sdk_generation = self.sdk_generation()?;
+        get_or_create(
+            &mut self.ray_reconstruction,
+            camera,
+            settings,
+            sdk_generation,
+            |context| &mut context.counters,
+            || {
+                DlssRayReconstruction::new(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    roughness_mode,
+                    depth_mode,
+                    Arc::clone(&self.sdk),
+                    device,
+                    queue,
+                )
+            },
+        )
+    }
+
+    fn sdk_generation(&self) -> Result<u64, DlssError> {
+        Ok(self
+            .sdk
+            .lock()
+            .map_err(|_| DlssError::SdkPoisoned)?
+            .generation)
+    }
+
+    /// Drop contexts that have not been requested since the previous call to this function.
+    pub fn evict_unused(&mut self) {
+        self.super_resolution.retain(|_, cached| cached.take_used());
+        self.ray_reconstruction
+            .retain(|_, cached| cached.take_used());
+    }
+
+    /// Drop all contexts for `camera`.
+    pub fn remove(&mut self, camera: &K) {
+        self.super_resolution.remove(camera);
+        self.ray_reconstruction.remove(camera);
+    }
+}
+
+struct CachedContext<T, S> {
+    context: T,
+    settings: S,
+    sdk_generation: u64,
+    used: bool,
+}
+
+impl<T, S> CachedContext<T, S> {
+    fn take_used(&mut self) -> bool {
+        std::mem::take(&mut self.used)
+    }
+}
+
+fn get_or_create<K: Hash + Eq, T, S: PartialEq>(
+    contexts: &mut HashMap<K, CachedContext<T, S>>,
+    camera: K,
+    settings: S,
+    sdk_generation: u64,
+    counters: fn(&mut T) -> &mut DlssCounters,
+    create: impl FnOnce() -> Result<T, DlssError>,
+) -> Result<&mut T, DlssError> {
+    let cached = match contexts.remove(&camera) {
+        Some(mut cached)
+            if cached.settings == settings && cached.sdk_generation == sdk_generation =>
+        {
+            cached.used = true;
+            cached
+        }
+        stale => {
+            // Destroy the old context before creating its replacement, to avoid holding both in VRAM
+            let previous_counters = stale.map(|mut stale| *counters(&mut stale.context));
+
+            let mut context = create()?;
+            if let Some(previous_counters) = previous_counters {
+                *counters(&mut context) = previous_counters.recreated();
+            }
+
+            CachedContext {
+                context,
+                settings,
+                sdk_generation,
+                used: true,
+            }
+        }
+    };
+
+    Ok(&mut contexts
+        .entry(camera)
+        .insert_entry(cached)
+        .into_mut()
+        .context)
+}
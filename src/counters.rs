@@ -0,0 +1,75 @@
+use std::{
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+use wgpu::Queue;
+
+/// Usage counters for a DLSS context, e.g. for performance HUDs or for detecting excessive history resets.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct DlssCounters {
+    /// Number of successful evaluations.
+    pub evaluations: u64,
+    /// Number of evaluations that reset temporal history.
+    pub history_resets: u64,
+    /// Number of times the context was recreated by a helper such as [`crate::DlssContextManager`].
+    pub recreations: u64,
+    /// Number of render calls rejected because of invalid render parameters.
+    pub validation_failures: u64,
+}
+
+impl DlssCounters {
+    pub(crate) fn record_evaluation(&mut self, reset: bool) {
+        self.evaluations += 1;
+        self.history_resets += reset as u64;
+    }
+
+    /// Counters for a context replacing one with these counters.
+    pub(crate) fn recreated(self) -> Self {
+        Self {
+            recreations: self.recreations + 1,
+            ..self
+        }
+    }
+}
+
+/// How long creating a DLSS context took, e.g. for showing progress while applying settings,
+/// or for spotting slow driver-side model compilation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct DlssCreationTiming {
+    /// CPU time spent in NGX creating the feature.
+    pub cpu_time: Duration,
+    /// Time from submitting the creation commands until the GPU completed them, or `None` if they have not
+    /// completed yet, or the context was created with `new_raw`.
+    ///
+    /// This is measured when wgpu next polls the device after completion, so is an upper bound.
+    pub gpu_time: Option<Duration>,
+}
+
+pub(crate) struct CreationTimer {
+    cpu_time: Duration,
+    gpu_time: Arc<OnceLock<Duration>>,
+}
+
+impl CreationTimer {
+    pub(crate) fn new(cpu_time: Duration) -> Self {
+        Self {
+            cpu_time,
+            gpu_time: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Call immediately after submitting the creation commands to `queue`.
+    pub(crate) fn time_gpu(&self, queue: &Queue) {
+        let (gpu_time, submitted) = (Arc::clone(&self.gpu_time), Instant::now());
+        queue.on_submitted_work_done(move || {
+            let _ = gpu_time.set(submitted.elapsed());
+        });
+    }
+
+    pub(crate) fn timing(&self) -> DlssCreationTiming {
+        DlssCreationTiming {
+            cpu_time: self.cpu_time,
+            gpu_time: self.gpu_time.get().copied(),
+        }
+    }
+}
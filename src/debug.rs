@@ -0,0 +1,248 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindingResource, BufferUsages, Color, ColorTargetState,
+    ColorWrites, CommandEncoder, Device, FragmentState, LoadOp, MultisampleState, Operations,
+    PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline,
+    RenderPipelineDescriptor, ShaderModule, ShaderModuleDescriptor, ShaderSource, StoreOp,
+    TextureFormat, TextureView, VertexState,
+    util::{BufferInitDescriptor, DeviceExt},
+};
+
+/// Renders motion vectors as colors, to check them for errors, which are the most common cause of DLSS artifacts.
+///
+/// Hue encodes the direction of motion, and brightness the magnitude. Static pixels are black.
+/// Camera motion should produce smooth gradients, and moving objects should be outlined exactly by their motion.
+pub struct DlssMotionVectorVisualizer {
+    device: Device,
+    shader: ShaderModule,
+    pipelines: HashMap<TextureFormat, RenderPipeline>,
+}
+
+impl DlssMotionVectorVisualizer {
+    /// Create a new [`DlssMotionVectorVisualizer`]. Pipelines are created lazily for each target format.
+    pub fn new(device: &Device) -> Self {
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("dlss_motion_vector_visualizer_shader"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("motion_vectors.wgsl"))),
+        });
+
+        Self {
+            device: device.clone(),
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Encode a render pass into `command_encoder` that visualizes `motion_vectors` over the whole of `target`.
+    ///
+    /// `motion_vector_scale` should match what is passed to DLSS, and convert the motion vectors to pixels.
+    /// Motion of `full_magnitude` pixels or more is drawn at full brightness.
+    ///
+    /// `target` must have been created with [`wgpu::TextureUsages::RENDER_ATTACHMENT`], and `motion_vectors` with [`wgpu::TextureUsages::TEXTURE_BINDING`].
+    /// Use a target at the render resolution to see every motion vector.
+    pub fn visualize(
+        &mut self,
+        motion_vectors: &TextureView,
+        motion_vector_scale: [f32; 2],
+        full_magnitude: f32,
+        target: &TextureView,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let target_format = target.texture().format();
+        let pipeline = self
+            .pipelines
+            .entry(target_format)
+            .or_insert_with(|| create_pipeline(&self.device, &self.shader, target_format));
+
+        let parameters = [
+            motion_vector_scale[0],
+            motion_vector_scale[1],
+            full_magnitude,
+            0.0,
+        ];
+        let parameters = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("dlss_motion_vector_visualizer_parameters"),
+            contents: &parameters
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect::<Vec<_>>(),
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("dlss_motion_vector_visualizer_bind_group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(motion_vectors),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: parameters.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("dlss_motion_vector_visualizer"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::BLACK),
+                    store: StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+/// Records the jitter offsets passed to DLSS over recent frames, and compares them against the suggested jitter,
+/// to catch jitter that is applied with the wrong sign or scale.
+///
+/// ```compile_fail
+/// let expected = context.suggested_jitter(frame_number, render_resolution);
+/// jitter_recorder.record(frame_number, render_parameters.jitter_offset, expected);
+/// if let Some(mismatch) = jitter_recorder.check() {
+///     warn!("DLSS jitter mismatch: {mismatch:?}");
+/// }
+/// ```
+pub struct DlssJitterRecorder {
+    capacity: usize,
+    samples: VecDeque<DlssJitterSample>,
+}
+
+/// A single frame recorded by [`DlssJitterRecorder`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DlssJitterSample {
+    pub frame_number: u32,
+    /// The jitter offset passed to DLSS.
+    pub jitter_offset: [f32; 2],
+    /// The jitter offset that was expected, e.g. from `suggested_jitter`.
+    pub expected_jitter_offset: [f32; 2],
+}
+
+/// How the recorded jitter differs from the expected jitter, as found by [`DlssJitterRecorder::check`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DlssJitterMismatch {
+    /// The jitter is consistently multiplied by a factor per axis, e.g. -1 if the sign is flipped,
+    /// or a small factor if it was passed in NDC or UV units rather than pixels.
+    Scaled { scale: [f32; 2] },
+    /// The jitter does not follow the expected sequence, e.g. because it lags behind by a frame.
+    Inconsistent,
+}
+
+impl DlssJitterRecorder {
+    /// Create a recorder that keeps the last `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Record the jitter offset passed to DLSS for a frame, alongside the offset that was expected.
+    pub fn record(
+        &mut self,
+        frame_number: u32,
+        jitter_offset: [f32; 2],
+        expected_jitter_offset: [f32; 2],
+    ) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        if self.capacity > 0 {
+            self.samples.push_back(DlssJitterSample {
+                frame_number,
+                jitter_offset,
+                expected_jitter_offset,
+            });
+        }
+    }
+
+    /// The recorded frames, oldest first.
+    pub fn samples(&self) -> impl ExactSizeIterator<Item = &DlssJitterSample> {
+        self.samples.iter()
+    }
+
+    /// Compare the recorded jitter with the expected jitter, returning `None` if they match.
+    pub fn check(&self) -> Option<DlssJitterMismatch> {
+        const TOLERANCE: f32 = 1e-3;
+
+        let mut scale = [None::<f32>; 2];
+        for sample in &self.samples {
+            for axis in 0..2 {
+                let actual = sample.jitter_offset[axis];
+                let expected = sample.expected_jitter_offset[axis];
+                if expected.abs() < TOLERANCE {
+                    if actual.abs() >= TOLERANCE {
+                        return Some(DlssJitterMismatch::Inconsistent);
+                    }
+                    continue;
+                }
+
+                let ratio = actual / expected;
+                match scale[axis] {
+                    Some(scale) if (ratio - scale).abs() > TOLERANCE * scale.abs().max(1.0) => {
+                        return Some(DlssJitterMismatch::Inconsistent);
+                    }
+                    Some(_) => {}
+                    None => scale[axis] = Some(ratio),
+                }
+            }
+        }
+
+        let scale = scale.map(|scale| scale.unwrap_or(1.0));
+        if scale.iter().all(|scale| (scale - 1.0).abs() <= TOLERANCE) {
+            None
+        } else {
+            Some(DlssJitterMismatch::Scaled { scale })
+        }
+    }
+
+    /// Forget all recorded frames.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+fn create_pipeline(
+    device: &Device,
+    shader: &ShaderModule,
+    target_format: TextureFormat,
+) -> RenderPipeline {
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("dlss_motion_vector_visualizer_pipeline"),
+        layout: None,
+        vertex: VertexState {
+            module: shader,
+            entry_point: Some("vertex"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(FragmentState {
+            module: shader,
+            entry_point: Some("fragment"),
+            compilation_options: Default::default(),
+            targets: &[Some(ColorTargetState {
+                format: target_format,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            })],
+        }),
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        multiview_mask: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,171 @@
+use std::{ops::RangeInclusive, time::Duration};
+
+/// Picks a render resolution each frame within a DLSS context's supported range, to hit a target GPU frame time.
+///
+/// Measure the GPU frame time yourself, e.g. using [`wgpu::Features::TIMESTAMP_QUERY`], and pass it to [`Self::update`] once per frame.
+/// Render the next frame at the returned resolution, into textures sized for the maximum render resolution, and pass the
+/// resolution to DLSS as the partial texture size, e.g. [`crate::super_resolution::DlssSuperResolutionRenderParameters::partial_texture_size`].
+///
+/// Unlike [`crate::DlssQualityController`], this never requires recreating the DLSS context.
+pub struct DlssDynamicResolution {
+    target_frame_time: Duration,
+    min_render_resolution: [u32; 2],
+    max_render_resolution: [u32; 2],
+    /// Fraction of the maximum render resolution per axis.
+    scale: f32,
+    /// Exponential moving average of the GPU frame time, in seconds.
+    average_frame_time: Option<f32>,
+}
+
+impl DlssDynamicResolution {
+    /// Create a new [`DlssDynamicResolution`], starting at the maximum of `render_resolution_range`,
+    /// e.g. from [`crate::super_resolution::DlssSuperResolution::render_resolution_range`].
+    pub fn new(
+        target_frame_time: Duration,
+        render_resolution_range: RangeInclusive<[u32; 2]>,
+    ) -> Self {
+        let (min_render_resolution, max_render_resolution) = ordered(render_resolution_range);
+        Self {
+            target_frame_time,
+            min_render_resolution,
+            max_render_resolution,
+            scale: 1.0,
+            average_frame_time: None,
+        }
+    }
+
+    /// Record the GPU frame time of the latest frame, returning the render resolution to use for the next frame.
+    pub fn update(&mut self, gpu_frame_time: Duration) -> [u32; 2] {
+        // Smooth out noise, so that the resolution does not oscillate between frames
+        let frame_time = gpu_frame_time.as_secs_f32();
+        let average_frame_time = match self.average_frame_time {
+            Some(average) => average + (frame_time - average) * 0.1,
+            None => frame_time,
+        };
+        self.average_frame_time = Some(average_frame_time);
+
+        // GPU time scales roughly with pixel count, so with the square of the per-axis scale.
+        // Aim slightly under the target, leaving headroom for the cost that does not scale with resolution.
+        if average_frame_time > 0.0 {
+            let target_frame_time = self.target_frame_time.as_secs_f32() * 0.9;
+            let correction = (target_frame_time / average_frame_time).sqrt();
+            // Limit the change per frame, as the average lags behind the effect of previous changes
+            self.scale = (self.scale * correction.clamp(0.95, 1.02)).clamp(self.min_scale(), 1.0);
+        }
+
+        self.render_resolution()
+    }
+
+    /// The render resolution for the next frame.
+    pub fn render_resolution(&self) -> [u32; 2] {
+        [0, 1].map(|axis| {
+            ((self.max_render_resolution[axis] as f32 * self.scale).round() as u32).clamp(
+                self.min_render_resolution[axis],
+                self.max_render_resolution[axis],
+            )
+        })
+    }
+
+    /// Change the target GPU frame time.
+    pub fn set_target_frame_time(&mut self, target_frame_time: Duration) {
+        self.target_frame_time = target_frame_time;
+    }
+
+    /// Change the supported range, e.g. after recreating the DLSS context. The current scale is kept.
+    pub fn set_render_resolution_range(
+        &mut self,
+        render_resolution_range: RangeInclusive<[u32; 2]>,
+    ) {
+        (self.min_render_resolution, self.max_render_resolution) = ordered(render_resolution_range);
+        self.scale = self.scale.clamp(self.min_scale(), 1.0);
+    }
+
+    /// The smallest per-axis scale at which both axes are within the supported range.
+    fn min_scale(&self) -> f32 {
+        (0..2)
+            .map(|axis| {
+                self.min_render_resolution[axis] as f32
+                    / self.max_render_resolution[axis].max(1) as f32
+            })
+            .fold(0.0, f32::max)
+            .min(1.0)
+    }
+}
+
+/// The bounds of `range` per axis, swapped where NGX reported a minimum above the maximum.
+fn ordered(range: RangeInclusive<[u32; 2]>) -> ([u32; 2], [u32; 2]) {
+    let (min, max) = range.into_inner();
+    (
+        [0, 1].map(|axis| min[axis].min(max[axis])),
+        [0, 1].map(|axis| min[axis].max(max[axis])),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET: Duration = Duration::from_millis(10);
+
+    /// GPU frame time of a frame at `render_resolution`, costing 20ms at 1000x1000.
+    fn frame_time(render_resolution: [u32; 2]) -> Duration {
+        let pixels = render_resolution[0] as f32 * render_resolution[1] as f32;
+        Duration::from_secs_f32(0.02 * pixels / 1_000_000.0)
+    }
+
+    #[test]
+    fn converges_to_target() {
+        let mut dynamic_resolution = DlssDynamicResolution::new(TARGET, [100, 100]..=[1000, 1000]);
+        let mut render_resolution = dynamic_resolution.render_resolution();
+        for _ in 0..1000 {
+            render_resolution = dynamic_resolution.update(frame_time(render_resolution));
+        }
+
+        // Aims for 90% of the target
+        let frame_time = frame_time(render_resolution).as_secs_f32();
+        assert!((frame_time - 0.009).abs() < 0.0005, "{frame_time}");
+    }
+
+    #[test]
+    fn step_limit() {
+        let mut dynamic_resolution = DlssDynamicResolution::new(TARGET, [100, 100]..=[1000, 1000]);
+        let mut previous = dynamic_resolution.render_resolution();
+        for _ in 0..10 {
+            let render_resolution = dynamic_resolution.update(Duration::from_secs(1));
+            assert!(render_resolution[0] < previous[0]);
+            assert!(render_resolution[0] as f32 >= previous[0] as f32 * 0.95 - 1.0);
+            previous = render_resolution;
+        }
+
+        // Grows more slowly than it shrinks, once the average frame time catches up
+        let lowest = previous;
+        for _ in 0..300 {
+            let render_resolution = dynamic_resolution.update(Duration::from_nanos(1));
+            assert!(render_resolution[0] as f32 >= previous[0] as f32 * 0.95 - 1.0);
+            assert!(render_resolution[0] as f32 <= previous[0] as f32 * 1.02 + 1.0);
+            previous = render_resolution;
+        }
+        assert!(previous[0] > lowest[0]);
+    }
+
+    #[test]
+    fn range_changes() {
+        let mut dynamic_resolution = DlssDynamicResolution::new(TARGET, [100, 100]..=[1000, 1000]);
+        for _ in 0..200 {
+            dynamic_resolution.update(Duration::from_secs(1));
+        }
+        assert_eq!(dynamic_resolution.render_resolution(), [100, 100]);
+
+        // The scale is clamped to keep both axes within the new range
+        dynamic_resolution.set_render_resolution_range([500, 200]..=[1000, 1000]);
+        assert_eq!(dynamic_resolution.render_resolution(), [500, 500]);
+
+        // Unordered bounds, as NGX may report
+        dynamic_resolution.set_render_resolution_range([800, 800]..=[400, 400]);
+        assert_eq!(dynamic_resolution.render_resolution(), [400, 400]);
+        assert_eq!(
+            dynamic_resolution.update(Duration::from_secs(1)),
+            [400, 400]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::env::var;
+
+/// The environment the application is running in.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum RuntimeEnvironment {
+    /// Running natively on the host operating system.
+    #[default]
+    Native,
+    /// Windows build running under Wine.
+    Wine,
+    /// Windows build running under Proton (Steam's distribution of Wine).
+    Proton,
+}
+
+impl RuntimeEnvironment {
+    /// Detects the environment the application is currently running in.
+    pub fn detect() -> Self {
+        if !running_under_wine() {
+            Self::Native
+        } else if var("STEAM_COMPAT_DATA_PATH").is_ok() {
+            Self::Proton
+        } else {
+            Self::Wine
+        }
+    }
+
+    /// Whether NvAPI is expected to be available.
+    ///
+    /// DLSS requires NvAPI. Under Wine and Proton, NvAPI is only provided when dxvk-nvapi is enabled,
+    /// e.g. via `PROTON_ENABLE_NVAPI=1`.
+    pub fn nvapi_available(&self) -> bool {
+        match self {
+            Self::Native => true,
+            Self::Wine | Self::Proton => ["PROTON_ENABLE_NVAPI", "DXVK_ENABLE_NVAPI"]
+                .into_iter()
+                .any(|name| var(name).is_ok_and(|value| value == "1")),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn running_under_wine() -> bool {
+    use std::ffi::{c_char, c_void};
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetModuleHandleA(module_name: *const c_char) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, proc_name: *const c_char) -> *mut c_void;
+    }
+
+    // Wine's ntdll exports wine_get_version(), which native Windows does not
+    unsafe {
+        let ntdll = GetModuleHandleA(c"ntdll.dll".as_ptr());
+        !ntdll.is_null() && !GetProcAddress(ntdll, c"wine_get_version".as_ptr()).is_null()
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn running_under_wine() -> bool {
+    false
+}
@@ -1,20 +1,29 @@
-use crate::nvsdk_ngx::*;
+use crate::{DlssInitOptions, RuntimeEnvironment, nvsdk_ngx::*};
 use std::{
     env::{self, var},
     ffi::{CString, OsStr, OsString},
     ptr,
 };
-use uuid::Uuid;
 
-pub fn with_feature_info<F, T>(project_id: Uuid, feature_id: NVSDK_NGX_Feature, callback: F) -> T
+pub fn with_feature_info<F, T>(
+    options: &DlssInitOptions,
+    feature_id: NVSDK_NGX_Feature,
+    callback: F,
+) -> T
 where
     F: FnOnce(&NVSDK_NGX_FeatureDiscoveryInfo) -> T,
 {
-    let project_id = CString::new(project_id.to_string()).unwrap();
-    let engine_version = CString::new(env!("CARGO_PKG_VERSION")).unwrap();
-    let data_path = os_str_to_wchar(env::temp_dir().as_os_str());
+    let project_id = CString::new(options.project_id.to_string()).unwrap();
+    let engine_version = CString::new(options.engine_version.as_str()).unwrap_or_default();
+    let data_path = os_str_to_wchar(
+        options
+            .application_data_path
+            .clone()
+            .unwrap_or_else(env::temp_dir)
+            .as_os_str(),
+    );
 
-    let shared_library_paths = get_shared_library_paths();
+    let shared_library_paths = get_shared_library_paths(options);
     let shared_library_path_pointers = shared_library_paths
         .iter()
         .map(Vec::as_ptr)
@@ -26,10 +35,9 @@ where
             Length: shared_library_paths.len() as u32,
         },
         InternalData: ptr::null_mut(),
-        // TODO: Allow configuring logging
         LoggingInfo: NVSDK_NGX_LoggingInfo {
             LoggingCallback: None,
-            MinimumLoggingLevel: NVSDK_NGX_Logging_Level_NVSDK_NGX_LOGGING_LEVEL_OFF,
+            MinimumLoggingLevel: options.logging_level.as_ngx(),
             DisableOtherLoggingSinks: false,
         },
     };
@@ -54,8 +62,12 @@ where
     (callback)(&feature_info)
 }
 
-fn get_shared_library_paths() -> Vec<Vec<wchar_t>> {
-    let mut shared_library_paths = vec![];
+fn get_shared_library_paths(options: &DlssInitOptions) -> Vec<Vec<wchar_t>> {
+    let mut shared_library_paths = options
+        .shared_library_paths
+        .iter()
+        .map(|path| os_str_to_wchar(path.as_os_str()))
+        .collect::<Vec<_>>();
 
     #[cfg(not(target_os = "windows"))]
     let platform = "Linux_x86_64";
@@ -73,6 +85,14 @@ fn get_shared_library_paths() -> Vec<Vec<wchar_t>> {
         shared_library_paths.push(os_str_to_wchar(&OsString::from(sdk_path)));
     }
 
+    // Wine and Proton install nvngx.dll into the system directory rather than the driver store
+    if RuntimeEnvironment::detect() != RuntimeEnvironment::Native
+        && let Ok(system_root) = var("SystemRoot")
+    {
+        let system_path = format!("{system_root}\\system32");
+        shared_library_paths.push(os_str_to_wchar(&OsString::from(system_path)));
+    }
+
     shared_library_paths
 }
 
@@ -0,0 +1,215 @@
+use ash::vk;
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use wgpu::{CommandEncoderDescriptor, Device, Queue, hal::api::Vulkan};
+
+/// Number of evaluations that can be in flight at once before their timings are dropped.
+const SLOT_COUNT: u32 = 4;
+/// Number of samples kept for computing [`DlssGpuTimeStatistics`].
+const SAMPLE_COUNT: usize = 120;
+
+/// Rolling statistics of the GPU time taken by DLSS evaluations, over the last 120 evaluations.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DlssGpuTimeStatistics {
+    /// Mean GPU time.
+    pub average: Duration,
+    /// 50th percentile GPU time.
+    pub median: Duration,
+    /// 95th percentile GPU time.
+    pub p95: Duration,
+    /// Highest GPU time.
+    pub max: Duration,
+    /// Number of evaluations the statistics were computed from.
+    pub sample_count: usize,
+}
+
+/// Measures the GPU time of DLSS evaluations using Vulkan timestamp queries.
+pub(crate) struct GpuTimer {
+    device: Device,
+    queue: Queue,
+    query_pool: vk::QueryPool,
+    timestamp_period: f32,
+    next_slot: u32,
+    slots: [SlotState; SLOT_COUNT as usize],
+    samples: VecDeque<Duration>,
+}
+
+#[derive(Default)]
+enum SlotState {
+    /// No evaluation is waiting to be read back.
+    #[default]
+    Idle,
+    /// An evaluation has been recorded, but its command buffer may not have been submitted yet.
+    Recorded,
+    /// The evaluation's command buffer has been submitted, and the flag is set once the queue has finished it.
+    Submitted(Arc<AtomicBool>),
+}
+
+impl GpuTimer {
+    pub(crate) fn new(device: &Device, queue: &Queue) -> Result<Self, vk::Result> {
+        let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("dlss_gpu_timer_reset"),
+        });
+        let (query_pool, timestamp_period) = unsafe {
+            let hal_device = device.as_hal::<Vulkan>().unwrap();
+            let timestamp_period = hal_device
+                .shared_instance()
+                .raw_instance()
+                .get_physical_device_properties(hal_device.raw_physical_device())
+                .limits
+                .timestamp_period;
+            let query_pool = hal_device.raw_device().create_query_pool(
+                &vk::QueryPoolCreateInfo::default()
+                    .query_type(vk::QueryType::TIMESTAMP)
+                    .query_count(SLOT_COUNT * 2),
+                None,
+            )?;
+
+            // Queries must be reset before use, including before their results are read back
+            command_encoder.as_hal_mut::<Vulkan, _, _>(|command_encoder| {
+                hal_device.raw_device().cmd_reset_query_pool(
+                    command_encoder.unwrap().raw_handle(),
+                    query_pool,
+                    0,
+                    SLOT_COUNT * 2,
+                );
+            });
+
+            (query_pool, timestamp_period)
+        };
+        queue.submit([command_encoder.finish()]);
+
+        Ok(Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            query_pool,
+            timestamp_period,
+            next_slot: 0,
+            slots: Default::default(),
+            samples: VecDeque::with_capacity(SAMPLE_COUNT),
+        })
+    }
+
+    /// Records `record` into `command_buffer`, surrounded by timestamp queries.
+    ///
+    /// The timing is only read back once the queue has finished the work submitted before the next call to this function,
+    /// so the command buffer must be submitted before then.
+    ///
+    /// # Safety
+    /// `command_buffer` must be in the recording state, outside of a render pass.
+    pub(crate) unsafe fn time<T>(
+        &mut self,
+        command_buffer: vk::CommandBuffer,
+        record: impl FnOnce() -> T,
+    ) -> T {
+        self.poll();
+
+        // Previously recorded evaluations have been submitted by now
+        for state in &mut self.slots {
+            if let SlotState::Recorded = state {
+                let completed = Arc::new(AtomicBool::new(false));
+                let completed_clone = Arc::clone(&completed);
+                self.queue
+                    .on_submitted_work_done(move || completed_clone.store(true, Ordering::Release));
+                *state = SlotState::Submitted(completed);
+            }
+        }
+
+        let slot = self.next_slot;
+        self.next_slot = (self.next_slot + 1) % SLOT_COUNT;
+        // If the previous evaluation using this slot is still in flight, its timing is dropped rather than waited on
+        self.slots[slot as usize] = SlotState::Recorded;
+
+        unsafe {
+            let hal_device = self.device.as_hal::<Vulkan>().unwrap();
+            let raw_device = hal_device.raw_device();
+            raw_device.cmd_reset_query_pool(command_buffer, self.query_pool, slot * 2, 2);
+            raw_device.cmd_write_timestamp(
+                command_buffer,
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                self.query_pool,
+                slot * 2,
+            );
+            let result = record();
+            raw_device.cmd_write_timestamp(
+                command_buffer,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                self.query_pool,
+                slot * 2 + 1,
+            );
+            result
+        }
+    }
+
+    /// Reads back the results of completed evaluations without blocking.
+    pub(crate) fn poll(&mut self) {
+        let hal_device = unsafe { self.device.as_hal::<Vulkan>().unwrap() };
+        for slot in 0..SLOT_COUNT {
+            // Until the submission has completed, the queries may still hold the results of the slot's previous use
+            let SlotState::Submitted(completed) = &self.slots[slot as usize] else {
+                continue;
+            };
+            if !completed.load(Ordering::Acquire) {
+                continue;
+            }
+
+            // Pairs of (timestamp, availability)
+            let mut timestamps = [[0u64; 2]; 2];
+            let result = unsafe {
+                hal_device.raw_device().get_query_pool_results(
+                    self.query_pool,
+                    slot * 2,
+                    &mut timestamps,
+                    vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WITH_AVAILABILITY,
+                )
+            };
+            if result.is_err() || timestamps.iter().any(|[_, available]| *available == 0) {
+                continue;
+            }
+
+            self.slots[slot as usize] = SlotState::Idle;
+            if self.samples.len() == SAMPLE_COUNT {
+                self.samples.pop_front();
+            }
+            let ticks = timestamps[1][0].saturating_sub(timestamps[0][0]);
+            self.samples.push_back(Duration::from_nanos(
+                (ticks as f64 * self.timestamp_period as f64) as u64,
+            ));
+        }
+    }
+
+    pub(crate) fn statistics(&self) -> Option<DlssGpuTimeStatistics> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let percentile = |p: f32| sorted[((sorted.len() - 1) as f32 * p).round() as usize];
+
+        Some(DlssGpuTimeStatistics {
+            average: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            max: *sorted.last().unwrap(),
+            sample_count: sorted.len(),
+        })
+    }
+}
+
+impl Drop for GpuTimer {
+    fn drop(&mut self) {
+        unsafe {
+            let hal_device = self.device.as_hal::<Vulkan>().unwrap();
+            hal_device
+                .raw_device()
+                .destroy_query_pool(self.query_pool, None);
+        }
+    }
+}
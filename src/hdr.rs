@@ -0,0 +1,194 @@
+use std::{borrow::Cow, collections::HashMap};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindingResource, BufferUsages, Color, ColorTargetState,
+    ColorWrites, CommandEncoder, Device, FragmentState, LoadOp, MultisampleState, Operations,
+    PipelineCompilationOptions, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
+    RenderPipeline, RenderPipelineDescriptor, ShaderModule, ShaderModuleDescriptor, ShaderSource,
+    StoreOp, TextureFormat, TextureView, VertexState,
+    util::{BufferInitDescriptor, DeviceExt},
+};
+
+/// Converts between HDR swapchain encodings and the linear radiance DLSS expects.
+///
+/// Use [`Self::decode`] on color that was already encoded for an HDR display before passing it to DLSS,
+/// and [`Self::encode`] on the DLSS output before presenting it.
+///
+/// Linear values are relative to `reference_white_nits`, e.g. the paper white brightness, so that 1.0 is reference white.
+/// Primaries are not converted, so PQ color stays in BT.2020 primaries and scRGB color in BT.709 primaries.
+///
+/// Conversions are done in a render pass rather than a compute pass, as swapchain formats such as
+/// [`TextureFormat::Rgb10a2Unorm`] usually do not support [`wgpu::TextureUsages::STORAGE_BINDING`].
+pub struct DlssHdrConversion {
+    device: Device,
+    shader: ShaderModule,
+    pipelines: HashMap<(TextureFormat, DlssHdrEncoding, bool), RenderPipeline>,
+}
+
+/// The transfer function of HDR color converted by [`DlssHdrConversion`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DlssHdrEncoding {
+    /// SMPTE ST 2084 (HDR10), typically with a [`TextureFormat::Rgb10a2Unorm`] swapchain.
+    Pq,
+    /// Linear extended sRGB, where 1.0 is 80 nits, typically with a [`TextureFormat::Rgba16Float`] swapchain.
+    ScRgb,
+}
+
+impl DlssHdrConversion {
+    /// Create a new [`DlssHdrConversion`]. Pipelines are created lazily for each target format and conversion.
+    pub fn new(device: &Device) -> Self {
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("dlss_hdr_conversion_shader"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("hdr.wgsl"))),
+        });
+
+        Self {
+            device: device.clone(),
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Encode a render pass into `command_encoder` that converts `encoding` encoded color in `source` to linear radiance in `target`.
+    ///
+    /// `target` should have a float format, e.g. [`TextureFormat::Rgba16Float`], to hold values above reference white.
+    /// `source` and `target` must be the same size. `target` must have been created with [`wgpu::TextureUsages::RENDER_ATTACHMENT`],
+    /// and `source` with [`wgpu::TextureUsages::TEXTURE_BINDING`].
+    pub fn decode(
+        &mut self,
+        source: &TextureView,
+        target: &TextureView,
+        encoding: DlssHdrEncoding,
+        reference_white_nits: f32,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        self.convert(
+            source,
+            target,
+            encoding,
+            true,
+            reference_white_nits,
+            command_encoder,
+        );
+    }
+
+    /// Encode a render pass into `command_encoder` that converts linear radiance in `source`, e.g. the DLSS output,
+    /// to `encoding` encoded color in `target`.
+    ///
+    /// `source` and `target` must be the same size. `target` must have been created with [`wgpu::TextureUsages::RENDER_ATTACHMENT`],
+    /// and `source` with [`wgpu::TextureUsages::TEXTURE_BINDING`].
+    pub fn encode(
+        &mut self,
+        source: &TextureView,
+        target: &TextureView,
+        encoding: DlssHdrEncoding,
+        reference_white_nits: f32,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        self.convert(
+            source,
+            target,
+            encoding,
+            false,
+            reference_white_nits,
+            command_encoder,
+        );
+    }
+
+    fn convert(
+        &mut self,
+        source: &TextureView,
+        target: &TextureView,
+        encoding: DlssHdrEncoding,
+        decode: bool,
+        reference_white_nits: f32,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let target_format = target.texture().format();
+        let pipeline = self
+            .pipelines
+            .entry((target_format, encoding, decode))
+            .or_insert_with(|| {
+                create_pipeline(&self.device, &self.shader, target_format, encoding, decode)
+            });
+
+        let parameters = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("dlss_hdr_conversion_parameters"),
+            contents: &reference_white_nits.to_ne_bytes(),
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("dlss_hdr_conversion_bind_group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(source),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: parameters.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("dlss_hdr_conversion"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::BLACK),
+                    store: StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+fn create_pipeline(
+    device: &Device,
+    shader: &ShaderModule,
+    target_format: TextureFormat,
+    encoding: DlssHdrEncoding,
+    decode: bool,
+) -> RenderPipeline {
+    let pq = encoding == DlssHdrEncoding::Pq;
+
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("dlss_hdr_conversion_pipeline"),
+        layout: None,
+        vertex: VertexState {
+            module: shader,
+            entry_point: Some("vertex"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(FragmentState {
+            module: shader,
+            entry_point: Some("fragment"),
+            compilation_options: PipelineCompilationOptions {
+                constants: &[
+                    ("PQ", if pq { 1.0 } else { 0.0 }),
+                    ("DECODE", if decode { 1.0 } else { 0.0 }),
+                ],
+                ..Default::default()
+            },
+            targets: &[Some(ColorTargetState {
+                format: target_format,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            })],
+        }),
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        multiview_mask: None,
+        cache: None,
+    })
+}
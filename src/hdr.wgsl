@@ -0,0 +1,49 @@
+// Whether the encoded color uses the PQ transfer function, rather than scRGB
+override PQ: bool;
+// Whether to convert from encoded color to linear radiance, rather than the reverse
+override DECODE: bool;
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> reference_white_nits: f32;
+
+// SMPTE ST 2084 constants
+const M1: f32 = 0.1593017578125;
+const M2: f32 = 78.84375;
+const C1: f32 = 0.8359375;
+const C2: f32 = 18.8515625;
+const C3: f32 = 18.6875;
+const PQ_MAX_NITS: f32 = 10000.0;
+const SCRGB_WHITE_NITS: f32 = 80.0;
+
+@vertex
+fn vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // Fullscreen triangle
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+}
+
+fn to_nits(encoded: vec3<f32>) -> vec3<f32> {
+    if PQ {
+        let p = pow(max(encoded, vec3<f32>(0.0)), vec3<f32>(1.0 / M2));
+        return pow(max(p - C1, vec3<f32>(0.0)) / (C2 - C3 * p), vec3<f32>(1.0 / M1)) * PQ_MAX_NITS;
+    }
+    return encoded * SCRGB_WHITE_NITS;
+}
+
+fn from_nits(nits: vec3<f32>) -> vec3<f32> {
+    if PQ {
+        let p = pow(clamp(nits / PQ_MAX_NITS, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(M1));
+        return pow((C1 + C2 * p) / (1.0 + C3 * p), vec3<f32>(M2));
+    }
+    return nits / SCRGB_WHITE_NITS;
+}
+
+@fragment
+fn fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let color = textureLoad(source, vec2<u32>(position.xy), 0);
+
+    if DECODE {
+        return vec4<f32>(to_nits(color.rgb) / reference_white_nits, color.a);
+    }
+    return vec4<f32>(from_nits(color.rgb * reference_white_nits), color.a);
+}
@@ -1,9 +1,9 @@
-use crate::{feature_info::with_feature_info, nvsdk_ngx::*};
+use crate::{RuntimeEnvironment, feature_info::with_feature_info, nvsdk_ngx::*};
 use ash::{Entry, vk::PhysicalDevice};
-use std::{ffi::CStr, ptr, slice};
+use std::{ffi::CStr, path::PathBuf, ptr, slice};
 use uuid::Uuid;
 use wgpu::{
-    Adapter, Device, DeviceDescriptor, Instance, InstanceDescriptor, Limits, Queue,
+    Adapter, Backends, Device, DeviceDescriptor, Instance, InstanceDescriptor, Limits, Queue,
     RequestDeviceError,
     hal::{
         DeviceError, InstanceError,
@@ -12,11 +12,22 @@ use wgpu::{
     },
 };
 
+/// The NGX features whose support is tracked by [`FeatureSupport`].
+const NGX_FEATURES: [NVSDK_NGX_Feature; 3] = [
+    NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling,
+    NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction,
+    FRAME_GENERATION,
+];
+
+/// Only queried for [`FeatureSupport::frame_generation_supported`]. Its extensions are not enabled, and query failures
+/// are reported as [`DlssUnsupportedReason::QueryFailed`] rather than as errors, as this crate does not implement it.
+const FRAME_GENERATION: NVSDK_NGX_Feature = NVSDK_NGX_Feature_NVSDK_NGX_Feature_FrameGeneration;
+
 /// Creates a wgpu [`Instance`] with the extensions required for DLSS.
 ///
 /// If the current system does not support a given feature, it will set the corresponding variable in `feature_support` to false.
 pub fn create_instance(
-    project_id: Uuid,
+    options: &DlssInitOptions,
     instance_descriptor: &InstanceDescriptor,
     feature_support: &mut FeatureSupport,
 ) -> Result<Instance, InitializationError> {
@@ -32,7 +43,7 @@ pub fn create_instance(
                 display: None,
             },
             Some(Box::new(|mut args| {
-                result = register_instance_extensions(project_id, &mut args, feature_support);
+                result = register_instance_extensions(options, &mut args, feature_support);
             })),
         )?;
         result?;
@@ -44,29 +55,55 @@ pub fn create_instance(
 /// Call this inside of [`wgpu::hal::vulkan::Instance::init_with_callback`] to register wgpu instance extensions
 /// necessary for DLSS.
 pub fn register_instance_extensions(
-    project_id: Uuid,
+    options: &DlssInitOptions,
     args: &mut CreateInstanceCallbackArgs,
     feature_support: &mut FeatureSupport,
 ) -> Result<(), RegisterInstanceExtensionsError> {
+    args.extensions
+        .extend(options.extra_instance_extensions.iter().copied());
+
+    if !options.request_super_resolution {
+        feature_support.set_unsupported(
+            NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling,
+            DlssUnsupportedReason::NotRequested,
+        );
+    }
+    if !options.request_ray_reconstruction {
+        feature_support.set_unsupported(
+            NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction,
+            DlssUnsupportedReason::NotRequested,
+        );
+    }
+    feature_support.low_latency_supported &= options.request_low_latency;
+
+    feature_support.runtime_environment = RuntimeEnvironment::detect();
+    if !feature_support.runtime_environment.nvapi_available() {
+        for feature_id in NGX_FEATURES {
+            feature_support.set_unsupported(feature_id, DlssUnsupportedReason::NvApiUnavailable);
+        }
+        return Ok(());
+    }
+
     let mut result = Ok(());
-    match required_instance_extensions(
-        project_id,
-        NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling,
-        args.entry,
-    ) {
-        Ok((extensions, true)) => args.extensions.extend(extensions),
-        Ok((_, false)) => feature_support.super_resolution_supported = false,
-        Err(err) => result = Err(err),
-    };
-    match required_instance_extensions(
-        project_id,
-        NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction,
-        args.entry,
-    ) {
-        Ok((extensions, true)) => args.extensions.extend(extensions),
-        Ok((_, false)) => feature_support.ray_reconstruction_supported = false,
-        Err(err) => result = Err(err),
-    };
+    for feature_id in NGX_FEATURES {
+        if !feature_support.supported(feature_id) {
+            continue;
+        }
+        match required_instance_extensions(options, feature_id, args.entry) {
+            Ok((_, None)) if feature_id == FRAME_GENERATION => {}
+            Ok((extensions, None)) => args.extensions.extend(extensions),
+            Ok((_, Some(missing_extension))) => feature_support.set_unsupported(
+                feature_id,
+                DlssUnsupportedReason::MissingInstanceExtension(
+                    missing_extension.to_string_lossy().into_owned(),
+                ),
+            ),
+            Err(err) if feature_id == FRAME_GENERATION => {
+                feature_support.set_unsupported(feature_id, DlssUnsupportedReason::QueryFailed(err))
+            }
+            Err(err) => result = Err(err),
+        };
+    }
     result
 }
 
@@ -76,7 +113,7 @@ pub fn register_instance_extensions(
 ///
 /// The provided [`Adapter`] must be using the Vulkan backend.
 pub fn request_device(
-    project_id: Uuid,
+    options: &DlssInitOptions,
     adapter: &Adapter,
     device_descriptor: &DeviceDescriptor,
     feature_support: &mut FeatureSupport,
@@ -92,12 +129,8 @@ pub fn request_device(
             &limits.unwrap_or(adapter.limits()),
             &device_descriptor.memory_hints,
             Some(Box::new(|mut args| {
-                result = register_device_extensions(
-                    project_id,
-                    &mut args,
-                    &raw_adapter,
-                    feature_support,
-                );
+                result =
+                    register_device_extensions(options, &mut args, &raw_adapter, feature_support);
             })),
         )?;
         result?;
@@ -106,50 +139,144 @@ pub fn request_device(
     }
 }
 
+/// Creates a wgpu [`Instance`], [`Adapter`], and [`Device`] for DLSS without any surface, e.g. for server-side or batch upscaling.
+///
+/// NVIDIA adapters are preferred. Low latency mode is disabled, as it requires a swapchain.
+///
+/// Returns [`InitializationError::NoAdapter`] if the instance has no Vulkan adapters.
+pub async fn request_headless_device(
+    options: &DlssInitOptions,
+    instance_descriptor: &InstanceDescriptor,
+    device_descriptor: &DeviceDescriptor<'_>,
+    feature_support: &mut FeatureSupport,
+) -> Result<(Instance, Adapter, Device, Queue), InitializationError> {
+    let instance = create_instance(options, instance_descriptor, feature_support)?;
+    let adapter = preferred_adapter(&instance).await?;
+
+    feature_support.low_latency_supported = false;
+    let (device, queue) =
+        request_device(options, &adapter, device_descriptor, feature_support, None)?;
+    Ok((instance, adapter, device, queue))
+}
+
+/// Checks which DLSS features are supported, using a temporary wgpu [`Instance`] and without creating a [`Device`],
+/// e.g. to disable DLSS settings in a launcher before the real device exists.
+///
+/// Support is checked for the adapter [`request_headless_device`] would pick. Applications that pick their adapter
+/// differently should use [`create_instance`] and [`request_device`] to check support for it instead.
+///
+/// Returns [`InitializationError::NoAdapter`] if the system has no Vulkan adapters.
+pub async fn probe_support(
+    options: &DlssInitOptions,
+) -> Result<FeatureSupport, InitializationError> {
+    let mut feature_support = FeatureSupport::default();
+    let instance = create_instance(
+        options,
+        &InstanceDescriptor::new_without_display_handle(),
+        &mut feature_support,
+    )?;
+    let adapter = preferred_adapter(&instance).await?;
+
+    unsafe {
+        let raw_adapter = adapter
+            .as_hal::<Vulkan>()
+            .ok_or(InitializationError::UnsupportedBackend)?;
+        let raw_instance = raw_adapter.shared_instance().raw_instance();
+        let raw_physical_device = raw_adapter.raw_physical_device();
+
+        feature_support.low_latency_supported &= raw_adapter
+            .physical_device_capabilities()
+            .supports_extension(ash::nv::low_latency2::NAME);
+        for feature_id in NGX_FEATURES {
+            if !feature_support.supported(feature_id) {
+                continue;
+            }
+            match unsupported_reason(
+                options,
+                feature_id,
+                &raw_adapter,
+                raw_instance.handle(),
+                raw_physical_device,
+            ) {
+                Ok((_, None)) => {}
+                Ok((_, Some(reason))) => feature_support.set_unsupported(feature_id, reason),
+                Err(err) if feature_id == FRAME_GENERATION => feature_support
+                    .set_unsupported(feature_id, DlssUnsupportedReason::QueryFailed(err)),
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    Ok(feature_support)
+}
+
+/// The adapter to use when the application does not pick one, preferring NVIDIA adapters.
+async fn preferred_adapter(instance: &Instance) -> Result<Adapter, InitializationError> {
+    const NVIDIA_VENDOR_ID: u32 = 0x10DE;
+
+    let mut adapters = instance.enumerate_adapters(Backends::VULKAN).await;
+    adapters.sort_by_key(|adapter| adapter.get_info().vendor != NVIDIA_VENDOR_ID);
+    adapters
+        .into_iter()
+        .next()
+        .ok_or(InitializationError::NoAdapter)
+}
+
 /// Call this inside of [`wgpu::hal::vulkan::Instance::init_with_callback`] to register wgpu instance extensions
 /// necessary for DLSS.
 pub fn register_device_extensions(
-    project_id: Uuid,
+    options: &DlssInitOptions,
     args: &mut CreateDeviceCallbackArgs,
     raw_adapter: &wgpu::hal::vulkan::Adapter,
     feature_support: &mut FeatureSupport,
 ) -> Result<(), RegisterInstanceExtensionsError> {
+    args.extensions
+        .extend(options.extra_device_extensions.iter().copied());
+
     let raw_instance = raw_adapter.shared_instance().raw_instance();
     let raw_physical_device = raw_adapter.raw_physical_device();
     let mut result = Ok(());
 
-    match required_device_extensions(
-        project_id,
-        NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling,
-        raw_adapter,
-        raw_instance.handle(),
-        raw_physical_device,
-    ) {
-        Ok((extensions, true)) => args.extensions.extend(extensions),
-        Ok((_, false)) => feature_support.super_resolution_supported = false,
-        Err(err) => result = Err(err),
-    };
-
-    match required_device_extensions(
-        project_id,
-        NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction,
-        raw_adapter,
-        raw_instance.handle(),
-        raw_physical_device,
-    ) {
-        Ok((extensions, true)) => args.extensions.extend(extensions),
-        Ok((_, false)) => feature_support.ray_reconstruction_supported = false,
-        Err(err) => result = Err(err),
-    };
+    // Used by crate::reflex
+    feature_support.low_latency_supported &= raw_adapter
+        .physical_device_capabilities()
+        .supports_extension(ash::nv::low_latency2::NAME);
+    if feature_support.low_latency_supported {
+        args.extensions.push(ash::nv::low_latency2::NAME);
+    }
+
+    for feature_id in NGX_FEATURES {
+        if !feature_support.supported(feature_id) {
+            continue;
+        }
+        match unsupported_reason(
+            options,
+            feature_id,
+            raw_adapter,
+            raw_instance.handle(),
+            raw_physical_device,
+        ) {
+            Ok((_, None)) if feature_id == FRAME_GENERATION => {}
+            Ok((extensions, None)) => args.extensions.extend(extensions),
+            Ok((_, Some(reason))) => feature_support.set_unsupported(feature_id, reason),
+            Err(err) if feature_id == FRAME_GENERATION => {
+                feature_support.set_unsupported(feature_id, DlssUnsupportedReason::QueryFailed(err))
+            }
+            Err(err) => result = Err(err),
+        };
+    }
     result
 }
 
 fn required_instance_extensions(
-    project_id: Uuid,
+    options: &DlssInitOptions,
     feature_id: NVSDK_NGX_Feature,
     entry: &Entry,
-) -> Result<(impl Iterator<Item = &'static CStr>, bool), RegisterInstanceExtensionsError> {
-    with_feature_info(project_id, feature_id, |feature_info| unsafe {
+) -> Result<
+    (impl Iterator<Item = &'static CStr>, Option<&'static CStr>),
+    RegisterInstanceExtensionsError,
+> {
+    with_feature_info(options, feature_id, |feature_info| unsafe {
         // Get required extension names
         let mut required_extensions = ptr::null_mut();
         let mut required_extension_count = 0;
@@ -166,24 +293,48 @@ fn required_instance_extensions(
 
         // Check that the required extensions are supported
         let supported_extensions = entry.enumerate_instance_extension_properties(None)?;
-        let extensions_supported = required_extensions.clone().all(|required_extension| {
-            supported_extensions
+        let missing_extension = required_extensions.clone().find(|required_extension| {
+            !supported_extensions
                 .iter()
                 .any(|extension| extension.extension_name_as_c_str() == Ok(required_extension))
         });
 
-        Ok((required_extensions, extensions_supported))
+        Ok((required_extensions, missing_extension))
     })
 }
 
-fn required_device_extensions(
-    project_id: Uuid,
+/// Returns the device extensions required by a feature, and why the adapter does not support the feature, if it doesn't.
+fn unsupported_reason(
+    options: &DlssInitOptions,
     feature_id: NVSDK_NGX_Feature,
     raw_adapter: &wgpu::hal::vulkan::Adapter,
     raw_instance: ash::vk::Instance,
     raw_physical_device: PhysicalDevice,
-) -> Result<(impl Iterator<Item = &'static CStr>, bool), RegisterInstanceExtensionsError> {
-    with_feature_info(project_id, feature_id, |feature_info| unsafe {
+) -> Result<
+    (
+        impl Iterator<Item = &'static CStr>,
+        Option<DlssUnsupportedReason>,
+    ),
+    RegisterInstanceExtensionsError,
+> {
+    with_feature_info(options, feature_id, |feature_info| unsafe {
+        // Check the adapter, driver, and OS
+        let mut requirement = NVSDK_NGX_FeatureRequirement {
+            FeatureSupported:
+                NVSDK_NGX_Feature_Support_Result_NVSDK_NGX_FeatureSupportResult_Supported,
+            MinHWArchitecture: 0,
+            MinOSVersion: [0; 255],
+        };
+        // Only used to explain why a feature is unsupported, so failures fall back to the extension check
+        let requirement_reason = check_ngx_result(NVSDK_NGX_VULKAN_GetFeatureRequirements(
+            raw_instance,
+            raw_physical_device,
+            feature_info,
+            &mut requirement,
+        ))
+        .ok()
+        .and_then(|()| unsupported_requirement(requirement.FeatureSupported));
+
         // Get required extension names
         let mut required_extensions = ptr::null_mut();
         let mut required_extension_count = 0;
@@ -201,22 +352,68 @@ fn required_device_extensions(
             .map(|extension| CStr::from_ptr(extension.extension_name.as_ptr()));
 
         // Check that the required extensions are supported
-        let extensions_supported = required_extensions.clone().all(|required_extension| {
-            raw_adapter
+        let missing_extension = required_extensions.clone().find(|required_extension| {
+            !raw_adapter
                 .physical_device_capabilities()
                 .supports_extension(required_extension)
         });
 
-        Ok((required_extensions, extensions_supported))
+        let reason = requirement_reason.or_else(|| {
+            missing_extension.map(|extension| {
+                DlssUnsupportedReason::MissingDeviceExtension(
+                    extension.to_string_lossy().into_owned(),
+                )
+            })
+        });
+        Ok((required_extensions, reason))
     })
 }
 
+fn unsupported_requirement(
+    feature_supported: NVSDK_NGX_Feature_Support_Result,
+) -> Option<DlssUnsupportedReason> {
+    if feature_supported
+        & NVSDK_NGX_Feature_Support_Result_NVSDK_NGX_FeatureSupportResult_AdapterUnsupported
+        != 0
+    {
+        Some(DlssUnsupportedReason::AdapterUnsupported)
+    } else if feature_supported
+        & NVSDK_NGX_Feature_Support_Result_NVSDK_NGX_FeatureSupportResult_DriverVersionUnsupported
+        != 0
+    {
+        Some(DlssUnsupportedReason::DriverVersionUnsupported)
+    } else if feature_supported
+        & NVSDK_NGX_Feature_Support_Result_NVSDK_NGX_FeatureSupportResult_OSVersionBelowMinimumSupported
+        != 0
+    {
+        Some(DlssUnsupportedReason::OsVersionUnsupported)
+    } else {
+        None
+    }
+}
+
 /// Which DLSS features are supported on the current system.
 pub struct FeatureSupport {
     /// DLSS Super Resolution (DLSS) is supported.
     pub super_resolution_supported: bool,
     /// DLSS Ray Reconstruction (DLSS-RR) is supported.
     pub ray_reconstruction_supported: bool,
+    /// DLSS Frame Generation (DLSS-FG) is supported.
+    ///
+    /// This crate does not yet implement frame generation, and so does not enable the Vulkan extensions it requires.
+    pub frame_generation_supported: bool,
+    /// Why [`Self::super_resolution_supported`] is false, if this crate disabled it.
+    pub super_resolution_unsupported_reason: Option<DlssUnsupportedReason>,
+    /// Why [`Self::ray_reconstruction_supported`] is false, if this crate disabled it.
+    pub ray_reconstruction_unsupported_reason: Option<DlssUnsupportedReason>,
+    /// Why [`Self::frame_generation_supported`] is false, if this crate disabled it.
+    pub frame_generation_unsupported_reason: Option<DlssUnsupportedReason>,
+    /// NVIDIA Reflex low latency mode is supported, via `VK_NV_low_latency2`. See [`crate::reflex`].
+    pub low_latency_supported: bool,
+    /// The environment the application is running in, detected during [`create_instance`].
+    ///
+    /// When running under Wine or Proton without NvAPI enabled, all DLSS features will be reported as unsupported.
+    pub runtime_environment: RuntimeEnvironment,
 }
 
 impl Default for FeatureSupport {
@@ -224,6 +421,153 @@ impl Default for FeatureSupport {
         Self {
             super_resolution_supported: true,
             ray_reconstruction_supported: true,
+            frame_generation_supported: true,
+            super_resolution_unsupported_reason: None,
+            ray_reconstruction_unsupported_reason: None,
+            frame_generation_unsupported_reason: None,
+            low_latency_supported: true,
+            runtime_environment: RuntimeEnvironment::Native,
+        }
+    }
+}
+
+impl FeatureSupport {
+    fn feature(
+        &mut self,
+        feature_id: NVSDK_NGX_Feature,
+    ) -> (&mut bool, &mut Option<DlssUnsupportedReason>) {
+        if feature_id == NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling {
+            (
+                &mut self.super_resolution_supported,
+                &mut self.super_resolution_unsupported_reason,
+            )
+        } else if feature_id == NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction {
+            (
+                &mut self.ray_reconstruction_supported,
+                &mut self.ray_reconstruction_unsupported_reason,
+            )
+        } else {
+            (
+                &mut self.frame_generation_supported,
+                &mut self.frame_generation_unsupported_reason,
+            )
+        }
+    }
+
+    fn supported(&self, feature_id: NVSDK_NGX_Feature) -> bool {
+        if feature_id == NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling {
+            self.super_resolution_supported
+        } else if feature_id == NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction {
+            self.ray_reconstruction_supported
+        } else {
+            self.frame_generation_supported
+        }
+    }
+
+    /// Marks a feature as unsupported, unless it already is.
+    fn set_unsupported(&mut self, feature_id: NVSDK_NGX_Feature, reason: DlssUnsupportedReason) {
+        let (supported, unsupported_reason) = self.feature(feature_id);
+        if *supported {
+            *supported = false;
+            *unsupported_reason = Some(reason);
+        }
+    }
+}
+
+/// Why a DLSS feature is unsupported, see [`FeatureSupport`].
+///
+/// Problems that only show up once NGX is initialized, such as a missing DLSS library, are reported by
+/// [`crate::DlssSdk::super_resolution_availability`] and [`crate::DlssSdk::ray_reconstruction_availability`] instead.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DlssUnsupportedReason {
+    /// The feature was disabled in [`DlssInitOptions`].
+    NotRequested,
+    /// NvAPI is not available, see [`RuntimeEnvironment::nvapi_available`].
+    NvApiUnavailable,
+    /// The Vulkan instance does not support this required extension.
+    MissingInstanceExtension(String),
+    /// The adapter does not support this required Vulkan device extension.
+    MissingDeviceExtension(String),
+    /// The adapter is not an NVIDIA GPU, or is too old.
+    AdapterUnsupported,
+    /// The installed driver is too old.
+    DriverVersionUnsupported,
+    /// The operating system is too old.
+    OsVersionUnsupported,
+    /// Querying the requirements of the feature failed.
+    ///
+    /// Only reported for [`FeatureSupport::frame_generation_supported`]. Failures for other features are returned as errors.
+    QueryFailed(RegisterInstanceExtensionsError),
+}
+
+/// Options for initializing DLSS, shared by [`create_instance`], [`request_device`], and [`crate::DlssSdk::new`].
+///
+/// The same options should be used for every call.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct DlssInitOptions {
+    /// Your application's NVIDIA project ID.
+    pub project_id: Uuid,
+    /// Whether to enable DLSS Super Resolution. If false, [`FeatureSupport::super_resolution_supported`] will be set to false.
+    pub request_super_resolution: bool,
+    /// Whether to enable DLSS Ray Reconstruction. If false, [`FeatureSupport::ray_reconstruction_supported`] will be set to false.
+    pub request_ray_reconstruction: bool,
+    /// Whether to enable `VK_NV_low_latency2`, used by [`crate::reflex`]. If false, [`FeatureSupport::low_latency_supported`] will be set to false.
+    pub request_low_latency: bool,
+    /// Additional directories to search for the DLSS libraries, before the default locations.
+    ///
+    /// Combined with [`crate::DlssSdk::reinitialize`], this allows switching DLSS library versions at runtime.
+    pub shared_library_paths: Vec<PathBuf>,
+    /// Additional Vulkan instance extensions to enable, alongside those required by DLSS.
+    pub extra_instance_extensions: Vec<&'static CStr>,
+    /// Additional Vulkan device extensions to enable, alongside those required by DLSS.
+    pub extra_device_extensions: Vec<&'static CStr>,
+    /// How much NGX should log to its log files.
+    pub logging_level: DlssLoggingLevel,
+    /// Directory NGX writes its logs and caches to. Defaults to [`std::env::temp_dir`] if `None`.
+    ///
+    /// Use your application's own cache directory so caches persist across runs, and so sandboxed installs can write to it.
+    pub application_data_path: Option<PathBuf>,
+    /// Whether [`crate::DlssSdk::new`] should check for, and download, over-the-air DLSS updates in the background.
+    pub check_for_updates: bool,
+    /// Your engine's version, reported to NGX. Defaults to the version of this crate.
+    pub engine_version: String,
+}
+
+impl DlssInitOptions {
+    /// Creates options for the given project ID, requesting all DLSS features and updates with logging disabled.
+    pub fn new(project_id: Uuid) -> Self {
+        Self {
+            project_id,
+            request_super_resolution: true,
+            request_ray_reconstruction: true,
+            request_low_latency: true,
+            shared_library_paths: Vec::new(),
+            extra_instance_extensions: Vec::new(),
+            extra_device_extensions: Vec::new(),
+            logging_level: DlssLoggingLevel::Off,
+            application_data_path: None,
+            check_for_updates: true,
+            engine_version: env!("CARGO_PKG_VERSION").to_owned(),
+        }
+    }
+}
+
+/// Verbosity of NGX logging.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DlssLoggingLevel {
+    #[default]
+    Off,
+    On,
+    Verbose,
+}
+
+impl DlssLoggingLevel {
+    pub(crate) fn as_ngx(&self) -> NVSDK_NGX_Logging_Level {
+        match self {
+            Self::Off => NVSDK_NGX_Logging_Level_NVSDK_NGX_LOGGING_LEVEL_OFF,
+            Self::On => NVSDK_NGX_Logging_Level_NVSDK_NGX_LOGGING_LEVEL_ON,
+            Self::Verbose => NVSDK_NGX_Logging_Level_NVSDK_NGX_LOGGING_LEVEL_VERBOSE,
         }
     }
 }
@@ -243,10 +587,29 @@ pub enum InitializationError {
     DlssError(#[from] DlssError),
     #[error("Provided adapter is not using the Vulkan backend")]
     UnsupportedBackend,
+    #[error("No Vulkan adapter is available")]
+    NoAdapter,
+}
+
+impl InitializationError {
+    /// A stable numeric code for this error, e.g. for bucketing failures in telemetry across crate versions.
+    ///
+    /// Codes are never changed or reused. [`Self::DlssError`] returns [`DlssError::code`], and other variants return codes from 1000.
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::InstanceError(_) => 1000,
+            Self::RequestDeviceError(_) => 1001,
+            Self::DeviceError(_) => 1002,
+            Self::VulkanError(_) => 1003,
+            Self::DlssError(err) => err.code(),
+            Self::UnsupportedBackend => 1004,
+            Self::NoAdapter => 1005,
+        }
+    }
 }
 
 /// Error returned by [`register_instance_extensions`].
-#[derive(thiserror::Error, Debug)]
+#[derive(thiserror::Error, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum RegisterInstanceExtensionsError {
     #[error(transparent)]
     VulkanError(#[from] ash::vk::Result),
@@ -0,0 +1,110 @@
+use crate::{
+    DlssError,
+    super_resolution::{DlssSuperResolution, DlssSuperResolutionRenderParameters},
+};
+use std::num::NonZeroU32;
+use wgpu::{Adapter, CommandBuffer, CommandEncoder, Extent3d, TexelCopyTextureInfo, Texture};
+
+/// Wraps a [`DlssSuperResolution`] context, only evaluating DLSS every `interval` frames to save GPU time.
+///
+/// Skipped frames repeat the most recent DLSS output, copying it into the new output texture if it changed.
+/// This trades temporal quality and responsiveness for cost, e.g. for battery powered devices.
+///
+/// DLSS treats consecutive evaluations as consecutive frames, so motion vectors passed on evaluated frames should cover
+/// the motion since the previous evaluated frame. Frames that request [`DlssSuperResolutionRenderParameters::reset`] are always evaluated.
+pub struct DlssIntervalSuperResolution {
+    context: DlssSuperResolution,
+    interval: NonZeroU32,
+    frames_since_evaluation: u32,
+    last_output: Option<Texture>,
+}
+
+impl DlssIntervalSuperResolution {
+    /// Wrap `context`, evaluating it every `interval` frames. An interval of 1 evaluates every frame.
+    pub fn new(context: DlssSuperResolution, interval: NonZeroU32) -> Self {
+        Self {
+            context,
+            interval,
+            frames_since_evaluation: 0,
+            last_output: None,
+        }
+    }
+
+    /// Change how often DLSS is evaluated.
+    pub fn set_interval(&mut self, interval: NonZeroU32) {
+        self.interval = interval;
+    }
+
+    /// How often DLSS is evaluated, in frames.
+    pub fn interval(&self) -> NonZeroU32 {
+        self.interval
+    }
+
+    /// Call once per frame instead of [`DlssSuperResolution::render`].
+    ///
+    /// Returns the DLSS command buffer on evaluated frames, following the same rules as [`DlssSuperResolution::render`].
+    /// On skipped frames, returns `None`, and any copy is recorded into `command_encoder`.
+    ///
+    /// When the output texture changes, both the previous and new output textures must have been created with
+    /// [`wgpu::TextureUsages::COPY_SRC`] and [`wgpu::TextureUsages::COPY_DST`] respectively.
+    pub fn render(
+        &mut self,
+        render_parameters: DlssSuperResolutionRenderParameters,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<Option<CommandBuffer>, DlssError> {
+        let output = render_parameters.dlss_output.texture();
+
+        let skip = match &self.last_output {
+            Some(_) if render_parameters.reset => false,
+            Some(_) => self.frames_since_evaluation + 1 < self.interval.get(),
+            None => false,
+        };
+
+        if skip {
+            self.frames_since_evaluation += 1;
+            if let Some(last_output) = self.last_output.as_ref().filter(|last| *last != output) {
+                let [width, height] = self.context.upscaled_resolution();
+                command_encoder.copy_texture_to_texture(
+                    TexelCopyTextureInfo {
+                        texture: last_output,
+                        mip_level: 0,
+                        origin: Default::default(),
+                        aspect: Default::default(),
+                    },
+                    TexelCopyTextureInfo {
+                        texture: output,
+                        mip_level: 0,
+                        origin: Default::default(),
+                        aspect: Default::default(),
+                    },
+                    Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                );
+                self.last_output = Some(output.clone());
+            }
+            return Ok(None);
+        }
+
+        let output = output.clone();
+        let command_buffer = self
+            .context
+            .render(render_parameters, command_encoder, adapter)?;
+        self.frames_since_evaluation = 0;
+        self.last_output = Some(output);
+        Ok(Some(command_buffer))
+    }
+
+    /// The wrapped context. Use it to query the render resolution and suggested jitter.
+    pub fn context(&self) -> &DlssSuperResolution {
+        &self.context
+    }
+
+    /// Stop skipping frames, returning the context.
+    pub fn into_inner(self) -> DlssSuperResolution {
+        self.context
+    }
+}
@@ -0,0 +1,205 @@
+/// Returns the `index`th element of the Halton low-discrepancy sequence with the given `base`, in the range `[0, 1)`.
+pub fn halton_sequence(mut index: u32, base: u32) -> f32 {
+    let mut f = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        f /= base as f32;
+        result += f * (index % base) as f32;
+        index = (index as f32 / base as f32).floor() as u32;
+    }
+    result
+}
+
+/// Jitter phases per upscaled pixel recommended by the DLSS programming guide.
+pub const BASE_JITTER_PHASE_COUNT: u32 = 8;
+
+/// Minimum number of jitter phases recommended by the DLSS Ray Reconstruction programming guide.
+pub const RAY_RECONSTRUCTION_MIN_JITTER_PHASE_COUNT: u32 = 32;
+
+/// Number of jitter phases to cycle through before repeating, for a given render and upscaled resolution.
+///
+/// Follows the DLSS programming guide: [`BASE_JITTER_PHASE_COUNT`] multiplied by the ratio of upscaled to render pixels,
+/// so that each upscaled pixel receives [`BASE_JITTER_PHASE_COUNT`] samples.
+/// NGX does not report a phase count itself.
+pub fn jitter_phase_count(render_resolution: [u32; 2], upscaled_resolution: [u32; 2]) -> u32 {
+    let render_pixels = render_resolution[0] as f32 * render_resolution[1] as f32;
+    let upscaled_pixels = upscaled_resolution[0] as f32 * upscaled_resolution[1] as f32;
+    (BASE_JITTER_PHASE_COUNT as f32 * upscaled_pixels / render_pixels.max(1.0)).round() as u32
+}
+
+/// Subpixel camera jitter for a given frame, using a Halton (2, 3) sequence of length `phase_count`.
+///
+/// Each component is in the range `[-0.5, 0.5)`, in units of render resolution pixels.
+pub fn jitter_offset(frame_number: u32, phase_count: u32) -> [f32; 2] {
+    let i = frame_number % phase_count.max(1);
+
+    [halton_sequence(i, 2) - 0.5, halton_sequence(i, 3) - 0.5]
+}
+
+/// Mip bias to apply when sampling textures, for a given render and upscaled resolution.
+pub fn mip_bias(render_resolution: [u32; 2], upscaled_resolution: [u32; 2]) -> f32 {
+    (render_resolution[0] as f32 / upscaled_resolution[0] as f32).log2() - 1.0
+}
+
+/// Converts a jitter offset in render resolution pixels to an NDC offset, for translating a projection matrix.
+///
+/// The result should shift NDC by `+offset`. For a column-major projection matrix, that means:
+/// * Right-handed perspective, where `w = -z` (e.g. `glam::Mat4::perspective_rh`): subtract it from the x and y components of the third column.
+/// * Left-handed perspective, where `w = z`: add it to the x and y components of the third column.
+/// * Orthographic, where `w = 1`: add it to the x and y components of the fourth column.
+///
+/// Equivalently, for any projection, multiply a translation by `(offset.x, offset.y, 0)` on the left of the projection matrix.
+/// Pixel space y points down, while NDC y points up.
+pub fn jitter_clip_space_offset(jitter_offset: [f32; 2], render_resolution: [u32; 2]) -> [f32; 2] {
+    [
+        2.0 * jitter_offset[0] / render_resolution[0] as f32,
+        -2.0 * jitter_offset[1] / render_resolution[1] as f32,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    #[test]
+    fn halton() {
+        for (index, expected) in [
+            (0, 0.0),
+            (1, 0.5),
+            (2, 0.25),
+            (3, 0.75),
+            (4, 0.125),
+            (5, 0.625),
+        ] {
+            assert_close(halton_sequence(index, 2), expected);
+        }
+        for (index, expected) in [
+            (0, 0.0),
+            (1, 1.0 / 3.0),
+            (2, 2.0 / 3.0),
+            (3, 1.0 / 9.0),
+            (4, 4.0 / 9.0),
+            (5, 7.0 / 9.0),
+        ] {
+            assert_close(halton_sequence(index, 3), expected);
+        }
+    }
+
+    #[test]
+    fn phase_count() {
+        let upscaled_resolution = [3840, 2160];
+        for (render_resolution, expected) in [
+            ([3840, 2160], 8),
+            ([2560, 1440], 18),
+            ([1920, 1080], 32),
+            ([1280, 720], 72),
+        ] {
+            assert_eq!(
+                jitter_phase_count(render_resolution, upscaled_resolution),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn offset_range() {
+        let phase_count = jitter_phase_count([1280, 720], [3840, 2160]);
+        for frame_number in 0..2 * phase_count {
+            for component in jitter_offset(frame_number, phase_count) {
+                assert!((-0.5..0.5).contains(&component), "{component}");
+            }
+        }
+        assert_eq!(
+            jitter_offset(phase_count + 3, phase_count),
+            jitter_offset(3, phase_count)
+        );
+        assert_eq!(jitter_offset(3, 0), jitter_offset(0, 0));
+    }
+
+    #[test]
+    fn mip_bias_values() {
+        assert_close(mip_bias([3840, 2160], [3840, 2160]), -1.0);
+        assert_close(mip_bias([1920, 1080], [3840, 2160]), -2.0);
+        assert_close(mip_bias([960, 540], [3840, 2160]), -3.0);
+        assert_close(
+            mip_bias([2560, 1440], [3840, 2160]),
+            (2.0f32 / 3.0).log2() - 1.0,
+        );
+    }
+
+    #[test]
+    fn clip_space_offset() {
+        // Positive pixel space y (down) becomes negative NDC y (up).
+        assert_eq!(
+            jitter_clip_space_offset([0.25, 0.25], [1920, 1080]),
+            [0.5 / 1920.0, -0.5 / 1080.0]
+        );
+        assert_eq!(
+            jitter_clip_space_offset([-0.5, -0.5], [1920, 1080]),
+            [-1.0 / 1920.0, 1.0 / 1080.0]
+        );
+
+        // A whole pixel spans 2 / resolution in NDC, so halving the resolution doubles the offset.
+        assert_eq!(
+            jitter_clip_space_offset([1.0, 1.0], [100, 50]),
+            [0.02, -0.04]
+        );
+        assert_eq!(
+            jitter_clip_space_offset([1.0, 1.0], [50, 25]),
+            [0.04, -0.08]
+        );
+    }
+
+    /// Projects `point` with the column-major `view_to_clip`, returning NDC x and y.
+    fn project(view_to_clip: [f32; 16], point: [f32; 3]) -> [f32; 2] {
+        let clip = [0, 1, 3].map(|row| {
+            (0..3)
+                .map(|column| view_to_clip[column * 4 + row] * point[column])
+                .sum::<f32>()
+                + view_to_clip[12 + row]
+        });
+        [clip[0] / clip[2], clip[1] / clip[2]]
+    }
+
+    #[test]
+    fn clip_space_offset_sign() {
+        let offset = jitter_clip_space_offset([0.25, -0.5], [100, 50]);
+        #[rustfmt::skip]
+        let right_handed = [
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, -1.0,
+            0.0, 0.0, 0.1, 0.0,
+        ];
+        let mut left_handed = right_handed;
+        left_handed[11] = 1.0;
+        #[rustfmt::skip]
+        let orthographic = [
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, -0.1, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ];
+
+        // Index of the first element of the column to add the offset to, and its sign
+        let cases = [
+            (right_handed, [0.2, 0.3, -2.0], 8, -1.0),
+            (left_handed, [0.2, 0.3, 2.0], 8, 1.0),
+            (orthographic, [0.2, 0.3, -2.0], 12, 1.0),
+        ];
+        for (view_to_clip, point, column, sign) in cases {
+            let mut jittered = view_to_clip;
+            jittered[column] += sign * offset[0];
+            jittered[column + 1] += sign * offset[1];
+
+            let [x, y] = project(view_to_clip, point);
+            let [jittered_x, jittered_y] = project(jittered, point);
+            assert!((jittered_x - x - offset[0]).abs() < 1e-6);
+            assert!((jittered_y - y - offset[1]).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    DlssArrayLayers, DlssError, DlssFeatureFlags, DlssPerfQualityMode, DlssSdk,
+    super_resolution::{
+        DlssSuperResolution, DlssSuperResolutionExposure, DlssSuperResolutionRenderParameters,
+    },
+};
+use std::sync::{Arc, Mutex};
+use wgpu::{
+    Adapter, CommandBuffer, CommandEncoder, Device, Queue, Texture, TextureView,
+    TextureViewDescriptor, TextureViewDimension,
+};
+
+/// Manages one [`DlssSuperResolution`] context per layer of a texture array or cubemap, such as reflection probes or stereo render targets.
+///
+/// Each layer keeps its own temporal history.
+pub struct DlssLayeredSuperResolution {
+    layers: Vec<DlssSuperResolution>,
+}
+
+/// Inputs and output resources needed for rendering [`DlssLayeredSuperResolution`].
+///
+/// Every texture must be a 2D array or cube texture with one layer per context.
+/// The fields have the same meaning as in [`DlssSuperResolutionRenderParameters`], and apply to every layer.
+pub struct DlssLayeredRenderParameters<'a> {
+    pub color: &'a Texture,
+    pub depth: &'a Texture,
+    pub motion_vectors: &'a Texture,
+    pub exposure: DlssSuperResolutionExposure<'a, Texture>,
+    pub bias: Option<&'a Texture>,
+    pub dlss_output: &'a Texture,
+    pub reset: bool,
+    pub jitter_offset: [f32; 2],
+    pub partial_texture_size: Option<[u32; 2]>,
+    pub motion_vector_scale: Option<[f32; 2]>,
+}
+
+impl DlssLayeredSuperResolution {
+    /// Create `layer_count` contexts, one per layer.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn new(
+        layer_count: u32,
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        sdk: Arc<Mutex<DlssSdk>>,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Self, DlssError> {
+        let layers = (0..layer_count)
+            .map(|_| {
+                DlssSuperResolution::new(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    Arc::clone(&sdk),
+                    device,
+                    queue,
+                )
+            })
+            .collect::<Result<_, DlssError>>()?;
+
+        Ok(Self { layers })
+    }
+
+    /// The context for each layer, in layer order. Use them to query the render resolution and suggested jitter.
+    pub fn layers(&self) -> &[DlssSuperResolution] {
+        &self.layers
+    }
+
+    /// Encode rendering commands for every layer.
+    ///
+    /// The resulting command buffers should be submitted in order immediately after the finished `command_encoder`,
+    /// following the same rules as [`DlssSuperResolution::render`].
+    ///
+    /// # Panics
+    /// Panics if a texture has fewer array layers than there are contexts.
+    pub fn render(
+        &mut self,
+        render_parameters: DlssLayeredRenderParameters,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<Vec<CommandBuffer>, DlssError> {
+        self.layers
+            .iter_mut()
+            .enumerate()
+            .map(|(layer, context)| {
+                let layer = layer as u32;
+                let color = layer_view(render_parameters.color, layer);
+                let depth = layer_view(render_parameters.depth, layer);
+                let motion_vectors = layer_view(render_parameters.motion_vectors, layer);
+                let exposure = match &render_parameters.exposure {
+                    DlssSuperResolutionExposure::Manual {
+                        exposure,
+                        exposure_scale,
+                        pre_exposure,
+                    } => Some((layer_view(exposure, layer), *exposure_scale, *pre_exposure)),
+                    DlssSuperResolutionExposure::Automatic => None,
+                };
+                let bias = render_parameters.bias.map(|bias| layer_view(bias, layer));
+                let dlss_output = layer_view(render_parameters.dlss_output, layer);
+
+                context.render_array_layers(
+                    DlssSuperResolutionRenderParameters {
+                        color: &color,
+                        depth: &depth,
+                        motion_vectors: &motion_vectors,
+                        exposure: match &exposure {
+                            Some((exposure, exposure_scale, pre_exposure)) => {
+                                DlssSuperResolutionExposure::Manual {
+                                    exposure,
+                                    exposure_scale: *exposure_scale,
+                                    pre_exposure: *pre_exposure,
+                                }
+                            }
+                            None => DlssSuperResolutionExposure::Automatic,
+                        },
+                        bias: bias.as_ref(),
+                        dlss_output: &dlss_output,
+                        reset: render_parameters.reset,
+                        jitter_offset: render_parameters.jitter_offset,
+                        partial_texture_size: render_parameters.partial_texture_size,
+                        output_subrect_base: None,
+                        motion_vector_scale: render_parameters.motion_vector_scale,
+                    },
+                    |_| DlssArrayLayers::single(layer),
+                    command_encoder,
+                    adapter,
+                )
+            })
+            .collect()
+    }
+}
+
+fn layer_view(texture: &Texture, layer: u32) -> TextureView {
+    assert!(
+        layer < texture.depth_or_array_layers(),
+        "Expected one texture array layer per context"
+    );
+
+    texture.create_view(&TextureViewDescriptor {
+        label: Some("dlss_layer"),
+        dimension: Some(TextureViewDimension::D2),
+        base_array_layer: layer,
+        array_layer_count: Some(1),
+        ..Default::default()
+    })
+}
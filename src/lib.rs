@@ -11,22 +11,22 @@
 //!
 //! ## API Usage
 //! ```compile_fail
-//! use dlss_wgpu::{FeatureSupport, DlssSdk, DlssPerfQualityMode, DlssFeatureFlags};
+//! use dlss_wgpu::{FeatureSupport, DlssInitOptions, DlssSdk, DlssPerfQualityMode, DlssFeatureFlags};
 //! use dlss_wgpu::super_resolution::{DlssSuperResolution, DlssSuperResolutionRenderParameters};
 //!
-//! let project_id = Uuid::parse_str("...").unwrap();
+//! let dlss_options = DlssInitOptions::new(Uuid::parse_str("...").unwrap());
 //! let mut feature_support = FeatureSupport::default();
 //!
 //! // Initialize wgpu
-//! let instance = dlss_wgpu::create_instance(project_id, &instance_descriptor, &mut feature_support).unwrap();
+//! let instance = dlss_wgpu::create_instance(&dlss_options, &instance_descriptor, &mut feature_support).unwrap();
 //! let adapter = instance.request_adapter(&adapter_options).await.unwrap();
-//! let (device, queue) = dlss_wgpu::request_device(project_id, &adapter, &device_descriptor, &mut feature_support, None).unwrap();
+//! let (device, queue) = dlss_wgpu::request_device(&dlss_options, &adapter, &device_descriptor, &mut feature_support, None).unwrap();
 //!
 //! // Check for feature support, if false don't create DLSS resources
 //! println!("DLSS supported: {}", feature_support.super_resolution_supported);
 //!
 //! // Create the SDK once per application
-//! let sdk = DlssSdk::new(project_id, device).expect("Failed to create DlssSdk");
+//! let sdk = DlssSdk::new(&dlss_options, device).expect("Failed to create DlssSdk");
 //!
 //! // Create a DLSS context once per camera or when DLSS settings change
 //! let mut context = DlssSuperResolution::new(
@@ -53,28 +53,153 @@
 //! queue.submit([command_encoder.finsh(), dlss_command_buffer]);
 //! ```
 
+#[cfg(not(feature = "mock"))]
+mod async_creation;
+#[cfg(all(feature = "renderdoc", not(feature = "mock")))]
+mod capture;
+#[cfg(not(feature = "mock"))]
+mod context_manager;
+#[cfg(not(feature = "mock"))]
+mod counters;
+mod dynamic_resolution;
+#[cfg(not(feature = "mock"))]
+mod environment;
 #[cfg(not(feature = "mock"))]
 mod feature_info;
 #[cfg(not(feature = "mock"))]
+mod gpu_timer;
+#[cfg(not(feature = "mock"))]
 mod initialization;
 #[cfg(not(feature = "mock"))]
+mod interval;
+#[cfg(not(feature = "mock"))]
+mod layered;
+mod memory_budget;
+#[cfg(not(feature = "mock"))]
 mod nvsdk_ngx;
+mod output_pool;
+#[cfg(not(feature = "mock"))]
+mod pass;
+mod perf_quality_mode;
+#[cfg(not(feature = "mock"))]
+mod photo_mode;
+#[cfg(not(feature = "mock"))]
+mod preset;
+mod quality_controller;
+#[cfg(not(feature = "mock"))]
+mod resizer;
+#[cfg(not(feature = "mock"))]
+mod retry;
 #[cfg(not(feature = "mock"))]
 mod sdk;
+#[cfg(not(feature = "mock"))]
+mod shared;
+#[cfg(not(feature = "mock"))]
+mod split_screen;
+#[cfg(not(feature = "mock"))]
+mod stable_hash;
+#[cfg(not(feature = "mock"))]
+mod stereo;
+#[cfg(not(feature = "mock"))]
+mod surface_output;
+#[cfg(not(feature = "mock"))]
+mod upscaler;
+#[cfg(not(feature = "mock"))]
+mod worker;
 
+/// Helper pass for copying the DLSS output to a surface texture.
+pub mod blit;
+/// Camera cut detection, for deciding when to reset DLSS history.
+pub mod camera_cut;
+/// Helper pass for compositing native resolution UI over the DLSS output.
+pub mod compose;
+/// Debug visualizations and checks for DLSS inputs.
+pub mod debug;
+/// HDR transfer function conversions for DLSS inputs and outputs.
+pub mod hdr;
+/// Camera jitter and mip bias utilities.
+///
+/// These follow section 3.7 of `$DLSS_SDK/doc/DLSS_Programming_Guide_Release.pdf`, and are also suitable for other TAA implementations.
+pub mod jitter;
+/// Pure wgpu stand-in for DLSS, for testing without NVIDIA hardware.
+pub mod mock;
+/// NVIDIA Image Scaling style spatial upscaler, as a fallback where DLSS is unsupported.
+pub mod nis;
+/// Raw Vulkan interop.
+#[cfg(not(feature = "mock"))]
+pub mod raw;
+/// The generated NGX bindings, for NGX functionality this crate does not wrap.
+#[cfg(all(feature = "raw-bindings", not(feature = "mock")))]
+pub mod raw_bindings;
 /// DLSS Ray Reconstruction.
 #[cfg(not(feature = "mock"))]
 pub mod ray_reconstruction;
+/// NVIDIA Reflex low latency mode, frame rate limiting, and latency markers.
+#[cfg(not(feature = "mock"))]
+pub mod reflex;
+/// MSAA resolve for DLSS inputs.
+pub mod resolve;
+/// Sharpening for the DLSS output.
+pub mod sharpen;
 /// DLSS Super Resolution.
 #[cfg(not(feature = "mock"))]
 pub mod super_resolution;
+/// Procedurally generated input textures, for testing and benchmarking.
+pub mod synthetic;
 
+#[cfg(not(feature = "mock"))]
+pub use async_creation::DlssSuperResolutionCreator;
+#[cfg(all(feature = "renderdoc", not(feature = "mock")))]
+pub use capture::capture_evaluation;
+#[cfg(not(feature = "mock"))]
+pub use context_manager::DlssContextManager;
+#[cfg(not(feature = "mock"))]
+pub use counters::{DlssCounters, DlssCreationTiming};
+pub use dynamic_resolution::DlssDynamicResolution;
+#[cfg(not(feature = "mock"))]
+pub use environment::RuntimeEnvironment;
+#[cfg(not(feature = "mock"))]
+pub use gpu_timer::DlssGpuTimeStatistics;
 #[cfg(not(feature = "mock"))]
 pub use initialization::{
-    FeatureSupport, InitializationError, create_instance, register_device_extensions,
-    register_instance_extensions, request_device,
+    DlssInitOptions, DlssLoggingLevel, DlssUnsupportedReason, FeatureSupport, InitializationError,
+    create_instance, probe_support, register_device_extensions, register_instance_extensions,
+    request_device, request_headless_device,
 };
 #[cfg(not(feature = "mock"))]
-pub use nvsdk_ngx::{DlssError, DlssFeatureFlags, DlssPerfQualityMode};
+pub use interval::DlssIntervalSuperResolution;
+#[cfg(not(feature = "mock"))]
+pub use layered::{DlssLayeredRenderParameters, DlssLayeredSuperResolution};
+pub use memory_budget::DlssMemoryUsage;
+#[cfg(not(feature = "mock"))]
+pub use nvsdk_ngx::{DlssArrayLayers, DlssError, DlssFeatureFlags};
+pub use output_pool::DlssOutputTexturePool;
+#[cfg(not(feature = "mock"))]
+pub use pass::{DlssPassDescriptor, DlssPassResource, DlssPassRole};
+pub use perf_quality_mode::{DlssParseError, DlssPerfQualityMode};
+#[cfg(not(feature = "mock"))]
+pub use photo_mode::DlssPhotoMode;
+#[cfg(not(feature = "mock"))]
+pub use preset::{DlssRayReconstructionPreset, DlssRenderPresets, DlssSuperResolutionPreset};
+pub use quality_controller::DlssQualityController;
+#[cfg(not(feature = "mock"))]
+pub use resizer::DlssSuperResolutionResizer;
+#[cfg(not(feature = "mock"))]
+pub use retry::DlssRetryPolicy;
+#[cfg(not(feature = "mock"))]
+pub use sdk::{DlssFeatureAvailability, DlssNgxFeature, DlssSdk, DlssSdkBuilder};
+#[cfg(not(feature = "mock"))]
+pub use shared::DlssSharedSuperResolution;
+#[cfg(not(feature = "mock"))]
+pub use split_screen::{DlssSplitScreen, DlssViewport};
+#[cfg(not(feature = "mock"))]
+pub use stereo::DlssStereo;
+#[cfg(not(feature = "mock"))]
+pub use surface_output::DlssSurfaceOutput;
+#[cfg(not(feature = "mock"))]
+pub use upscaler::{
+    DlssTemporalUpscaler, DlssUpscaler, DlssUpscalerRayReconstructionInputs,
+    DlssUpscalerRenderParameters,
+};
 #[cfg(not(feature = "mock"))]
-pub use sdk::DlssSdk;
+pub use worker::{DlssWorker, DlssWorkerContextInfo};
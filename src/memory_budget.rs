@@ -0,0 +1,61 @@
+use ash::vk;
+use wgpu::{Device, hal::api::Vulkan};
+
+/// Device-local VRAM usage and budget reported by `VK_EXT_memory_budget`, for reacting to memory pressure,
+/// e.g. with [`crate::DlssQualityController::update_memory_usage`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DlssMemoryUsage {
+    /// Bytes currently used by this process, summed over device-local heaps.
+    pub usage: u64,
+    /// Bytes this process can use before allocations may fail or affect performance, summed over device-local heaps.
+    pub budget: u64,
+}
+
+impl DlssMemoryUsage {
+    /// Query the current VRAM usage of `device`.
+    ///
+    /// Returns `None` if `VK_EXT_memory_budget` is not enabled on `device`, which wgpu enables whenever it is supported.
+    pub fn query(device: &Device) -> Option<Self> {
+        unsafe {
+            let hal_device = device.as_hal::<Vulkan>()?;
+            if !hal_device
+                .enabled_device_extensions()
+                .contains(&ash::ext::memory_budget::NAME)
+            {
+                return None;
+            }
+
+            let mut budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+            let mut memory_properties =
+                vk::PhysicalDeviceMemoryProperties2::default().push_next(&mut budget_properties);
+            hal_device
+                .shared_instance()
+                .raw_instance()
+                .get_physical_device_memory_properties2(
+                    hal_device.raw_physical_device(),
+                    &mut memory_properties,
+                );
+
+            let heaps = memory_properties.memory_properties.memory_heaps_as_slice();
+            let device_local = (0..heaps.len()).filter(|&heap| {
+                heaps[heap]
+                    .flags
+                    .contains(vk::MemoryHeapFlags::DEVICE_LOCAL)
+            });
+            Some(Self {
+                usage: device_local
+                    .clone()
+                    .map(|heap| budget_properties.heap_usage[heap])
+                    .sum(),
+                budget: device_local
+                    .map(|heap| budget_properties.heap_budget[heap])
+                    .sum(),
+            })
+        }
+    }
+
+    /// Usage as a percentage of the budget, comparable to [`wgpu::MemoryBudgetThresholds`].
+    pub fn percentage(&self) -> u8 {
+        (self.usage.saturating_mul(100) / self.budget.max(1)).min(u8::MAX as u64) as u8
+    }
+}
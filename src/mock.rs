@@ -0,0 +1,304 @@
+use crate::{
+    DlssPerfQualityMode,
+    jitter::{
+        RAY_RECONSTRUCTION_MIN_JITTER_PHASE_COUNT, jitter_offset, jitter_phase_count, mip_bias,
+    },
+};
+use std::{borrow::Cow, collections::HashMap, ops::RangeInclusive};
+use wgpu::{
+    AddressMode, BindGroupDescriptor, BindGroupEntry, BindingResource, BufferUsages,
+    CommandEncoder, ComputePassDescriptor, ComputePipeline, ComputePipelineDescriptor, Device,
+    FilterMode, Sampler, SamplerDescriptor, ShaderModuleDescriptor, ShaderSource, TextureFormat,
+    TextureView,
+    util::{BufferInitDescriptor, DeviceExt},
+};
+
+/// Stand-in for `DlssSuperResolution` that bilinearly upscales the color input using a compute shader.
+///
+/// Useful for exercising DLSS integrations on machines without NVIDIA hardware.
+/// The output is not temporally accumulated, and is much lower quality than DLSS.
+pub struct MockSuperResolution {
+    upscaled_resolution: [u32; 2],
+    render_resolution: [u32; 2],
+    device: Device,
+    sampler: Sampler,
+    pipelines: HashMap<TextureFormat, ComputePipeline>,
+    jitter_phase_offset: u32,
+}
+
+impl MockSuperResolution {
+    /// Create a new [`MockSuperResolution`] object.
+    ///
+    /// The render resolution is [`DlssPerfQualityMode::approximate_render_resolution`].
+    pub fn new(
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        device: &Device,
+    ) -> Self {
+        let render_resolution =
+            perf_quality_mode.approximate_render_resolution(upscaled_resolution);
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("mock_super_resolution_sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            upscaled_resolution,
+            render_resolution,
+            device: device.clone(),
+            sampler,
+            pipelines: HashMap::new(),
+            jitter_phase_offset: 0,
+        }
+    }
+
+    /// Encode rendering commands for the mock upscaler into `command_encoder`.
+    ///
+    /// # Panics
+    /// Panics if the format of [`MockSuperResolutionRenderParameters::dlss_output`] is not one of
+    /// `Rgba8Unorm`, `Rgba16Float`, `Rgba32Float`, `Rgb10a2Unorm`, or `Rg11b10Ufloat`.
+    pub fn render(
+        &mut self,
+        render_parameters: MockSuperResolutionRenderParameters,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let color_size = render_parameters.color.texture().size();
+        let color_size = [color_size.width as f32, color_size.height as f32];
+        let partial_texture_size = render_parameters
+            .partial_texture_size
+            .unwrap_or(self.render_resolution);
+        let parameters = [
+            partial_texture_size[0] as f32 / color_size[0],
+            partial_texture_size[1] as f32 / color_size[1],
+            -render_parameters.jitter_offset[0] / color_size[0],
+            -render_parameters.jitter_offset[1] / color_size[1],
+        ];
+        let parameters = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("mock_super_resolution_parameters"),
+            contents: &parameters
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect::<Vec<_>>(),
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let output_format = render_parameters.dlss_output.texture().format();
+        let pipeline = self
+            .pipelines
+            .entry(output_format)
+            .or_insert_with(|| create_pipeline(&self.device, output_format));
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("mock_super_resolution_bind_group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(render_parameters.color),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&self.sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(render_parameters.dlss_output),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: parameters.as_entire_binding(),
+                },
+            ],
+        });
+
+        let output_size = render_parameters.dlss_output.texture().size();
+        let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("mock_super_resolution"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(
+            output_size.width.div_ceil(8),
+            output_size.height.div_ceil(8),
+            1,
+        );
+    }
+
+    /// Suggested subpixel camera jitter for a given frame.
+    pub fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
+        jitter_offset(
+            frame_number.wrapping_add(self.jitter_phase_offset),
+            self.suggested_jitter_phase_count(render_resolution),
+        )
+    }
+
+    /// Offset the sequence returned by [`Self::suggested_jitter`] by `offset` phases.
+    ///
+    /// Give simultaneous cameras, such as stereo eyes, different offsets so that they do not sample identical jitter each frame.
+    /// The offset must be set again after recreating the context.
+    pub fn set_jitter_phase_offset(&mut self, offset: u32) {
+        self.jitter_phase_offset = offset;
+    }
+
+    /// The offset set by [`Self::set_jitter_phase_offset`], 0 by default.
+    pub fn jitter_phase_offset(&self) -> u32 {
+        self.jitter_phase_offset
+    }
+
+    /// Suggested number of jitter phases to cycle through before repeating, used by [`Self::suggested_jitter`].
+    pub fn suggested_jitter_phase_count(&self, render_resolution: [u32; 2]) -> u32 {
+        jitter_phase_count(render_resolution, self.upscaled_resolution)
+    }
+
+    /// Suggested mip bias to apply when sampling textures.
+    pub fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
+        mip_bias(render_resolution, self.upscaled_resolution)
+    }
+
+    /// The upscaled resolution the mock upscaler will output at.
+    pub fn upscaled_resolution(&self) -> [u32; 2] {
+        self.upscaled_resolution
+    }
+
+    /// The resolution the camera should render at, pre-upscaling.
+    pub fn render_resolution(&self) -> [u32; 2] {
+        self.render_resolution
+    }
+
+    /// Like [`Self::render_resolution`], but returns a range of values for use with dynamic resolution scaling.
+    pub fn render_resolution_range(&self) -> RangeInclusive<[u32; 2]> {
+        self.render_resolution..=self.upscaled_resolution
+    }
+
+    /// The range of upscale factors (upscaled width divided by render width) matching [`Self::render_resolution_range`].
+    pub fn upscale_factor_range(&self) -> RangeInclusive<f32> {
+        1.0..=self.upscaled_resolution[0] as f32 / self.render_resolution[0] as f32
+    }
+}
+
+/// Stand-in for `DlssRayReconstruction` that bilinearly upscales the color input, ignoring the ray tracing guide buffers.
+///
+/// Suggests the same longer jitter sequences as `DlssRayReconstruction`.
+pub struct MockRayReconstruction {
+    upscaler: MockSuperResolution,
+}
+
+impl MockRayReconstruction {
+    /// Create a new [`MockRayReconstruction`] object. See [`MockSuperResolution::new`].
+    pub fn new(
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        device: &Device,
+    ) -> Self {
+        Self {
+            upscaler: MockSuperResolution::new(upscaled_resolution, perf_quality_mode, device),
+        }
+    }
+
+    /// Encode rendering commands for the mock upscaler into `command_encoder`. See [`MockSuperResolution::render`].
+    pub fn render(
+        &mut self,
+        render_parameters: MockSuperResolutionRenderParameters,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        self.upscaler.render(render_parameters, command_encoder);
+    }
+
+    /// Suggested subpixel camera jitter for a given frame.
+    pub fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
+        jitter_offset(
+            frame_number.wrapping_add(self.upscaler.jitter_phase_offset),
+            self.suggested_jitter_phase_count(render_resolution),
+        )
+    }
+
+    /// Offset the sequence returned by [`Self::suggested_jitter`] by `offset` phases.
+    pub fn set_jitter_phase_offset(&mut self, offset: u32) {
+        self.upscaler.set_jitter_phase_offset(offset);
+    }
+
+    /// The offset set by [`Self::set_jitter_phase_offset`], 0 by default.
+    pub fn jitter_phase_offset(&self) -> u32 {
+        self.upscaler.jitter_phase_offset()
+    }
+
+    /// Suggested number of jitter phases to cycle through before repeating, used by [`Self::suggested_jitter`].
+    pub fn suggested_jitter_phase_count(&self, render_resolution: [u32; 2]) -> u32 {
+        self.upscaler
+            .suggested_jitter_phase_count(render_resolution)
+            .max(RAY_RECONSTRUCTION_MIN_JITTER_PHASE_COUNT)
+    }
+
+    /// Suggested mip bias to apply when sampling textures.
+    pub fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
+        self.upscaler.suggested_mip_bias(render_resolution)
+    }
+
+    /// The upscaled resolution the mock upscaler will output at.
+    pub fn upscaled_resolution(&self) -> [u32; 2] {
+        self.upscaler.upscaled_resolution()
+    }
+
+    /// The resolution the camera should render at, pre-upscaling.
+    pub fn render_resolution(&self) -> [u32; 2] {
+        self.upscaler.render_resolution()
+    }
+
+    /// Like [`Self::render_resolution`], but returns a range of values for use with dynamic resolution scaling.
+    pub fn render_resolution_range(&self) -> RangeInclusive<[u32; 2]> {
+        self.upscaler.render_resolution_range()
+    }
+}
+
+/// Inputs and output resources needed for rendering [`MockSuperResolution`] and [`MockRayReconstruction`].
+pub struct MockSuperResolutionRenderParameters<'a> {
+    /// Main color view of your camera.
+    pub color: &'a TextureView,
+    /// The texture the mock upscaler outputs to.
+    ///
+    /// Must have been created with [`wgpu::TextureUsages::STORAGE_BINDING`].
+    pub dlss_output: &'a TextureView,
+    /// Subpixel jitter that was applied to your camera.
+    pub jitter_offset: [f32; 2],
+    /// Optionally use only a specific subrect of the input textures, rather than the whole textures.
+    pub partial_texture_size: Option<[u32; 2]>,
+}
+
+fn create_pipeline(device: &Device, output_format: TextureFormat) -> ComputePipeline {
+    let output_format = wgsl_storage_format(output_format)
+        .unwrap_or_else(|| panic!("Unsupported mock DLSS output format {output_format:?}"));
+
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("mock_super_resolution_shader"),
+        source: ShaderSource::Wgsl(Cow::Owned(
+            include_str!("mock.wgsl").replace("OUTPUT_FORMAT", output_format),
+        )),
+    });
+
+    device.create_compute_pipeline(&ComputePipelineDescriptor {
+        label: Some("mock_super_resolution_pipeline"),
+        layout: None,
+        module: &shader,
+        entry_point: Some("upscale"),
+        compilation_options: Default::default(),
+        cache: None,
+    })
+}
+
+/// The WGSL name of a storage texture format supported as an output by the pure wgpu upscalers.
+pub(crate) fn wgsl_storage_format(format: TextureFormat) -> Option<&'static str> {
+    match format {
+        TextureFormat::Rgba8Unorm => Some("rgba8unorm"),
+        TextureFormat::Rgba16Float => Some("rgba16float"),
+        TextureFormat::Rgba32Float => Some("rgba32float"),
+        TextureFormat::Rgb10a2Unorm => Some("rgb10a2unorm"),
+        TextureFormat::Rg11b10Ufloat => Some("rg11b10ufloat"),
+        _ => None,
+    }
+}
@@ -0,0 +1,20 @@
+struct Parameters {
+    uv_scale: vec2<f32>,
+    uv_offset: vec2<f32>,
+}
+
+@group(0) @binding(0) var color: texture_2d<f32>;
+@group(0) @binding(1) var color_sampler: sampler;
+@group(0) @binding(2) var output: texture_storage_2d<OUTPUT_FORMAT, write>;
+@group(0) @binding(3) var<uniform> parameters: Parameters;
+
+@compute @workgroup_size(8, 8, 1)
+fn upscale(@builtin(global_invocation_id) id: vec3<u32>) {
+    let output_size = textureDimensions(output);
+    if any(id.xy >= output_size) {
+        return;
+    }
+
+    let uv = (vec2<f32>(id.xy) + 0.5) / vec2<f32>(output_size) * parameters.uv_scale + parameters.uv_offset;
+    textureStore(output, id.xy, textureSampleLevel(color, color_sampler, uv, 0.0));
+}
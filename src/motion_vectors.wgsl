@@ -0,0 +1,34 @@
+struct Parameters {
+    motion_vector_scale: vec2<f32>,
+    full_magnitude: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0) var motion_vectors: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> parameters: Parameters;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vertex(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Fullscreen triangle
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return VertexOutput(vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0), uv);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = textureDimensions(motion_vectors);
+    let coordinates = min(vec2<u32>(in.uv * vec2<f32>(size)), size - 1u);
+    let motion = textureLoad(motion_vectors, coordinates, 0).xy * parameters.motion_vector_scale;
+
+    // Hue encodes direction, brightness encodes magnitude in pixels
+    let hue = (atan2(motion.y, motion.x) / 6.2831853 + 1.0) % 1.0;
+    let value = saturate(length(motion) / parameters.full_magnitude);
+    let rgb = saturate(abs((hue * 6.0 + vec3<f32>(0.0, 4.0, 2.0)) % 6.0 - 3.0) - 1.0);
+
+    return vec4<f32>(rgb * value, 1.0);
+}
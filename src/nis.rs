@@ -0,0 +1,167 @@
+use crate::{DlssPerfQualityMode, jitter::mip_bias, mock::wgsl_storage_format};
+use std::{borrow::Cow, collections::HashMap, ops::RangeInclusive};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindingResource, BufferUsages, CommandEncoder,
+    ComputePassDescriptor, ComputePipeline, ComputePipelineDescriptor, Device,
+    ShaderModuleDescriptor, ShaderSource, TextureFormat, TextureView,
+    util::{BufferInitDescriptor, DeviceExt},
+};
+
+/// Spatial upscaler in the style of NVIDIA Image Scaling (NIS), for GPUs where DLSS is not supported,
+/// e.g. when [`crate::FeatureSupport::super_resolution_supported`] is false.
+///
+/// Color is scaled with a 6x6 tap Lanczos filter, then sharpened with a tunable strength, limited to the range of the
+/// nearest input texels to avoid ringing. This is a reimplementation rather than NVIDIA's reference shader, so output differs slightly.
+///
+/// Being spatial, it has no temporal history. Do not jitter the camera, and render with anti-aliasing applied.
+pub struct NisUpscaler {
+    upscaled_resolution: [u32; 2],
+    render_resolution: [u32; 2],
+    device: Device,
+    pipelines: HashMap<TextureFormat, ComputePipeline>,
+}
+
+impl NisUpscaler {
+    /// Create a new [`NisUpscaler`] object.
+    ///
+    /// The render resolution is [`DlssPerfQualityMode::approximate_render_resolution`].
+    pub fn new(
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        device: &Device,
+    ) -> Self {
+        Self {
+            upscaled_resolution,
+            render_resolution: perf_quality_mode.approximate_render_resolution(upscaled_resolution),
+            device: device.clone(),
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Encode upscaling commands into `command_encoder`.
+    ///
+    /// # Panics
+    /// Panics if the format of [`NisRenderParameters::output`] is not one of
+    /// `Rgba8Unorm`, `Rgba16Float`, `Rgba32Float`, `Rgb10a2Unorm`, or `Rg11b10Ufloat`.
+    pub fn render(
+        &mut self,
+        render_parameters: NisRenderParameters,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let input_size = render_parameters
+            .partial_texture_size
+            .unwrap_or(self.render_resolution);
+        let parameters = [
+            input_size[0] as f32,
+            input_size[1] as f32,
+            render_parameters.sharpness.clamp(0.0, 1.0),
+            0.0,
+        ];
+        let parameters = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nis_upscaler_parameters"),
+            contents: &parameters
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect::<Vec<_>>(),
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let output_format = render_parameters.output.texture().format();
+        let pipeline = self
+            .pipelines
+            .entry(output_format)
+            .or_insert_with(|| create_pipeline(&self.device, output_format));
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("nis_upscaler_bind_group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(render_parameters.color),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(render_parameters.output),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: parameters.as_entire_binding(),
+                },
+            ],
+        });
+
+        let output_size = render_parameters.output.texture().size();
+        let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("nis_upscaler"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(
+            output_size.width.div_ceil(8),
+            output_size.height.div_ceil(8),
+            1,
+        );
+    }
+
+    /// Suggested mip bias to apply when sampling textures.
+    pub fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
+        mip_bias(render_resolution, self.upscaled_resolution)
+    }
+
+    /// The upscaled resolution the upscaler will output at.
+    pub fn upscaled_resolution(&self) -> [u32; 2] {
+        self.upscaled_resolution
+    }
+
+    /// The resolution the camera should render at, pre-upscaling.
+    pub fn render_resolution(&self) -> [u32; 2] {
+        self.render_resolution
+    }
+
+    /// Like [`Self::render_resolution`], but returns a range of values for use with dynamic resolution scaling.
+    pub fn render_resolution_range(&self) -> RangeInclusive<[u32; 2]> {
+        self.render_resolution..=self.upscaled_resolution
+    }
+
+    /// The range of upscale factors (upscaled width divided by render width) matching [`Self::render_resolution_range`].
+    pub fn upscale_factor_range(&self) -> RangeInclusive<f32> {
+        1.0..=self.upscaled_resolution[0] as f32 / self.render_resolution[0] as f32
+    }
+}
+
+/// Inputs and output resources needed for rendering [`NisUpscaler`].
+pub struct NisRenderParameters<'a> {
+    /// Main color view of your camera, with anti-aliasing applied.
+    pub color: &'a TextureView,
+    /// The texture the upscaler outputs to.
+    ///
+    /// Must have been created with [`wgpu::TextureUsages::STORAGE_BINDING`].
+    pub output: &'a TextureView,
+    /// Sharpening strength, from 0.0 (none) to 1.0.
+    pub sharpness: f32,
+    /// Optionally use only a specific subrect of the input textures, rather than the whole textures.
+    pub partial_texture_size: Option<[u32; 2]>,
+}
+
+fn create_pipeline(device: &Device, output_format: TextureFormat) -> ComputePipeline {
+    let output_format = wgsl_storage_format(output_format)
+        .unwrap_or_else(|| panic!("Unsupported NIS output format {output_format:?}"));
+
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("nis_upscaler_shader"),
+        source: ShaderSource::Wgsl(Cow::Owned(
+            include_str!("nis.wgsl").replace("OUTPUT_FORMAT", output_format),
+        )),
+    });
+
+    device.create_compute_pipeline(&ComputePipelineDescriptor {
+        label: Some("nis_upscaler_pipeline"),
+        layout: None,
+        module: &shader,
+        entry_point: Some("upscale"),
+        compilation_options: Default::default(),
+        cache: None,
+    })
+}
@@ -0,0 +1,64 @@
+struct Parameters {
+    input_size: vec2<f32>,
+    sharpness: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0) var color: texture_2d<f32>;
+@group(0) @binding(1) var output: texture_storage_2d<OUTPUT_FORMAT, write>;
+@group(0) @binding(2) var<uniform> parameters: Parameters;
+
+const PI: f32 = 3.14159265;
+
+fn lanczos3(x: f32) -> f32 {
+    let x_abs = abs(x);
+    if x_abs < 1e-5 {
+        return 1.0;
+    }
+    if x_abs >= 3.0 {
+        return 0.0;
+    }
+    return 3.0 * sin(PI * x) * sin(PI * x / 3.0) / (PI * PI * x * x);
+}
+
+fn load(coordinates: vec2<i32>) -> vec4<f32> {
+    let max_coordinates = vec2<i32>(parameters.input_size) - 1;
+    return textureLoad(color, clamp(coordinates, vec2<i32>(0), max_coordinates), 0);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn upscale(@builtin(global_invocation_id) id: vec3<u32>) {
+    let output_size = textureDimensions(output);
+    if any(id.xy >= output_size) {
+        return;
+    }
+
+    let position = (vec2<f32>(id.xy) + 0.5) / vec2<f32>(output_size) * parameters.input_size - 0.5;
+    let base = vec2<i32>(floor(position));
+    let fraction = position - floor(position);
+
+    // 6x6 tap Lanczos scaling
+    var sum = vec4<f32>(0.0);
+    var weight_sum = 0.0;
+    for (var y = -2; y <= 3; y++) {
+        let weight_y = lanczos3(f32(y) - fraction.y);
+        for (var x = -2; x <= 3; x++) {
+            let weight = lanczos3(f32(x) - fraction.x) * weight_y;
+            sum += load(base + vec2<i32>(x, y)) * weight;
+            weight_sum += weight;
+        }
+    }
+    let scaled = sum / weight_sum;
+
+    // Adaptive sharpening against the bilinear result, limited to the range of the nearest texels to avoid ringing
+    let c00 = load(base);
+    let c10 = load(base + vec2<i32>(1, 0));
+    let c01 = load(base + vec2<i32>(0, 1));
+    let c11 = load(base + vec2<i32>(1, 1));
+    let bilinear = mix(mix(c00, c10, fraction.x), mix(c01, c11, fraction.x), fraction.y);
+    let minimum = min(min(c00, c10), min(c01, c11));
+    let maximum = max(max(c00, c10), max(c01, c11));
+
+    let sharpened = scaled + (scaled - bilinear) * parameters.sharpness;
+    textureStore(output, id.xy, clamp(sharpened, minimum, maximum));
+}
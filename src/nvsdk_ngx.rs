@@ -19,45 +19,20 @@ type VkImageView = ash::vk::ImageView;
 type VkInstance = ash::vk::Instance;
 type VkPhysicalDevice = ash::vk::PhysicalDevice;
 
+use crate::DlssPerfQualityMode;
 use ash::vk::{
     ImageAspectFlags, ImageSubresourceRange, REMAINING_ARRAY_LAYERS, REMAINING_MIP_LEVELS,
 };
-use wgpu::{Adapter, TextureUsages, TextureView, wgc::api::Vulkan};
-
-/// How much DLSS should upscale by.
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
-pub enum DlssPerfQualityMode {
-    /// Let DLSS decide.
-    #[default]
-    Auto,
-    /// Anti-aliasing only, no upscaling.
-    Dlaa,
-    Quality,
-    Balanced,
-    Performance,
-    UltraPerformance,
-}
+use std::ptr;
+use wgpu::{Adapter, Device, TextureUsages, TextureView, wgc::api::Vulkan};
 
 impl DlssPerfQualityMode {
     pub(crate) fn as_perf_quality_value(
         &self,
         upscaled_resolution: [u32; 2],
     ) -> NVSDK_NGX_PerfQuality_Value {
-        match self {
-            Self::Auto => {
-                let mega_pixels =
-                    (upscaled_resolution[0] * upscaled_resolution[1]) as f32 / 1_000_000.0;
-
-                if mega_pixels < 2.03 {
-                    NVSDK_NGX_PerfQuality_Value_NVSDK_NGX_PerfQuality_Value_DLAA
-                } else if mega_pixels < 3.68 {
-                    NVSDK_NGX_PerfQuality_Value_NVSDK_NGX_PerfQuality_Value_MaxQuality
-                } else if mega_pixels < 8.29 {
-                    NVSDK_NGX_PerfQuality_Value_NVSDK_NGX_PerfQuality_Value_MaxPerf
-                } else {
-                    NVSDK_NGX_PerfQuality_Value_NVSDK_NGX_PerfQuality_Value_UltraPerformance
-                }
-            }
+        match self.resolve(upscaled_resolution) {
+            Self::Auto | Self::Custom(_) => unreachable!(),
             Self::Dlaa => NVSDK_NGX_PerfQuality_Value_NVSDK_NGX_PerfQuality_Value_DLAA,
             Self::Quality => NVSDK_NGX_PerfQuality_Value_NVSDK_NGX_PerfQuality_Value_MaxQuality,
             Self::Balanced => NVSDK_NGX_PerfQuality_Value_NVSDK_NGX_PerfQuality_Value_Balanced,
@@ -85,6 +60,8 @@ bitflags::bitflags! {
         /// Motion vector values in [`crate::DlssRenderParameters::motion_vectors`] contain jitter.
         const JitteredMotionVectors = NVSDK_NGX_DLSS_Feature_Flags_NVSDK_NGX_DLSS_Feature_Flags_MVJittered;
         /// Camera is using a reverse depth buffer for [`crate::DlssRenderParameters::depth`].
+        ///
+        /// Use [`DlssFeatureFlags::projection_has_inverted_depth`] to determine this from the camera's projection matrix.
         const InvertedDepth = NVSDK_NGX_DLSS_Feature_Flags_NVSDK_NGX_DLSS_Feature_Flags_DepthInverted;
         /// Have DLSS apply auto-exposure.
         const AutoExposure = NVSDK_NGX_DLSS_Feature_Flags_NVSDK_NGX_DLSS_Feature_Flags_AutoExposure;
@@ -96,6 +73,19 @@ bitflags::bitflags! {
 }
 
 impl DlssFeatureFlags {
+    /// Returns whether `view_to_clip` maps closer points to greater depth values, meaning [`DlssFeatureFlags::InvertedDepth`] should be set.
+    ///
+    /// `view_to_clip` is a column-major projection matrix. Orthographic projections are assumed to look down the -Z axis.
+    pub fn projection_has_inverted_depth(view_to_clip: [f32; 16]) -> bool {
+        if view_to_clip[11] != 0.0 {
+            // Perspective: NDC depth is a + b / distance, and decreases with distance when b > 0
+            view_to_clip[14] > 0.0
+        } else {
+            // Orthographic: NDC depth is a - z_scale * distance
+            view_to_clip[10] > 0.0
+        }
+    }
+
     pub(crate) fn as_flags(&self) -> NVSDK_NGX_DLSS_Feature_Flags {
         let mut flags = *self;
         flags.remove(DlssFeatureFlags::OutputSubrect);
@@ -104,74 +94,121 @@ impl DlssFeatureFlags {
 }
 
 /// Errors thrown by DLSS.
-#[derive(thiserror::Error, Debug)]
+///
+/// Each variant has a stable numeric code, returned by [`Self::code`], that is never changed or reused.
+#[derive(thiserror::Error, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(u32)]
 pub enum DlssError {
     #[error(
         "The NGX SDK or a specific feature is not supported by the current system, hardware, and/or graphics API."
     )]
-    FeatureNotSupported,
+    FeatureNotSupported = 1,
     #[error(
         "An error occurred within the underlying platform, which includes the graphics API in use, the operating system, or other system libraries and dependencies that are not part of the NGX SDK, such as NvAPI. Consult the NGX logs and the graphics API's validation layers for detailed information."
     )]
-    PlatformError,
+    PlatformError = 2,
     #[error(
         "The NGX feature could not be created because a feature with identical parameters already exists, and the feature does not support multiple identical instances."
     )]
-    FeatureAlreadyExists,
+    FeatureAlreadyExists = 3,
     #[error("A feature associated with the provided handle could not be found.")]
-    FeatureNotFound,
+    FeatureNotFound = 4,
     #[error(
         "One or more provided parameters had an incorrect value or type, or a required parameter was not provided."
     )]
-    InvalidParameters,
+    InvalidParameters = 5,
     #[error(
         "The feature requires a scratch buffer, but none was provided or the provided buffer is too small. Use NVSDK_NGX_GetScratchBufferSize to determine the necessary size."
     )]
-    ScratchBufferTooSmall,
+    ScratchBufferTooSmall = 6,
     #[error(
         "A function that requires the NGX SDK to be initialized was called before the SDK was properly initialized."
     )]
-    NotInitialized,
+    NotInitialized = 7,
     #[error("One or more input buffers supplied to the feature had an unsupported format.")]
-    UnsupportedInputFormat,
+    UnsupportedInputFormat = 8,
     #[error(
         "The feature requires read/write access to output buffers, but one or more provided buffers did not have the correct access flags (UAV in D3D11/D3D12)."
     )]
-    RWFlagMissing,
+    RWFlagMissing = 9,
     #[error("A required input parameter was not provided.")]
-    MissingInput,
+    MissingInput = 10,
     #[error(
         "The requested feature could not be initialized, likely because the library for that feature could not be found."
     )]
-    UnableToInitializeFeature,
+    UnableToInitializeFeature = 11,
     #[error(
         "A function was used which requires a newer version of the NVIDIA Display Driver or feature library than is currently installed."
     )]
-    OutOfDate,
+    OutOfDate = 12,
     #[error("An operation could not be completed because the system lacked sufficient GPU memory.")]
-    OutOfGPUMemory,
+    OutOfGPUMemory = 13,
     #[error("One or more buffers provided to the feature had an unsupported format.")]
-    UnsupportedFormat,
+    UnsupportedFormat = 14,
     #[error(
         "The SDK does not have the necessary write permissions for the path specified in InApplicationDataPath."
     )]
-    UnableToWriteToAppDataPath,
+    UnableToWriteToAppDataPath = 15,
     #[error(
         "A parameter supplied to the feature is either unsupported by the current version or has an unsupported value."
     )]
-    UnsupportedParameter,
+    UnsupportedParameter = 16,
     #[error(
         "NVIDIA has restricted the use of this feature in the current application. Contact NVIDIA for further information."
     )]
-    Denied,
+    Denied = 17,
     #[error(
         "The requested feature or functionality has not been implemented in the current version of the NGX SDK, display driver, or feature library."
     )]
-    NotImplemented,
+    NotImplemented = 18,
+    #[error("The DlssSdk mutex was poisoned by a panic on another thread while it was locked.")]
+    SdkPoisoned = 19,
+    #[error(
+        "A DlssSdk already exists for this device. Only one DlssSdk may exist per device at a time."
+    )]
+    AlreadyInitialized = 20,
+    #[error(
+        "The DlssSdk was reinitialized after this DLSS context was created. Recreate the context."
+    )]
+    SdkReinitialized = 21,
+    #[error(
+        "The DLSS output texture has an sRGB format, which cannot be written to as a storage image. Use a non-sRGB format, and apply sRGB encoding afterwards."
+    )]
+    SrgbOutput = 22,
+    #[error(
+        "The adapter passed to DLSS does not belong to the same physical device as the DlssSdk."
+    )]
+    DeviceMismatch = 23,
+    #[error("The surface has not been configured, and so does not have a swapchain.")]
+    SurfaceNotConfigured = 24,
+    #[error(
+        "The motion vector texture size or scale is inconsistent with DlssFeatureFlags::LowResolutionMotionVectors."
+    )]
+    MotionVectorMismatch = 25,
+}
+
+impl DlssError {
+    /// A stable numeric code for this error, e.g. for bucketing failures in telemetry across crate versions.
+    pub fn code(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Whether the error may go away by retrying the same operation later, which is only the case for running out of GPU memory.
+    ///
+    /// Other errors are fatal, and will recur until the inputs, system, or SDK change. See [`crate::DlssRetryPolicy`].
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::OutOfGPUMemory)
+    }
 }
 
 pub fn check_ngx_result(result: NVSDK_NGX_Result) -> Result<(), DlssError> {
-    match result {
+    try_check_ngx_result(result).unwrap_or_else(|| unreachable!())
+}
+
+/// Like [`check_ngx_result`], but returns `None` for result codes this crate does not know instead of panicking,
+/// for values reported by the driver.
+pub fn try_check_ngx_result(result: NVSDK_NGX_Result) -> Option<Result<(), DlssError>> {
+    let result = match result {
         NVSDK_NGX_Result_Success => Ok(()),
         NVSDK_NGX_Result_FAIL_FeatureNotSupported => Err(DlssError::FeatureNotSupported),
         NVSDK_NGX_RESULT_FAIL_PlatformError => Err(DlssError::PlatformError),
@@ -195,11 +232,83 @@ pub fn check_ngx_result(result: NVSDK_NGX_Result) -> Result<(), DlssError> {
         NVSDK_NGX_RESULT_FAIL_UnsupportedParameter => Err(DlssError::UnsupportedParameter),
         NVSDK_NGX_RESULT_FAIL_Denied => Err(DlssError::Denied),
         NVSDK_NGX_RESULT_FAIL_NotImplemented => Err(DlssError::NotImplemented),
-        _ => unreachable!(),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Checks that `adapter` is the adapter `device` was created from.
+pub fn check_adapter(device: &Device, adapter: &Adapter) -> Result<(), DlssError> {
+    let (adapter, device) = unsafe {
+        (
+            adapter.as_hal::<Vulkan>().unwrap().raw_physical_device(),
+            device.as_hal::<Vulkan>().unwrap().raw_physical_device(),
+        )
+    };
+    if adapter != device {
+        return Err(DlssError::DeviceMismatch);
+    }
+    Ok(())
+}
+
+/// Checks that the motion vector texture size and scale are consistent with [`DlssFeatureFlags::LowResolutionMotionVectors`].
+///
+/// Without the flag, motion vectors must cover the upscaled resolution. With the flag, motion vectors at the
+/// upscaled resolution paired with a smaller color texture are rejected, as they would cause smearing artifacts.
+pub fn check_motion_vectors(
+    feature_flags: DlssFeatureFlags,
+    upscaled_resolution: [u32; 2],
+    color_size: [u32; 2],
+    motion_vectors_size: [u32; 2],
+    motion_vector_scale: Option<[f32; 2]>,
+) -> Result<(), DlssError> {
+    if let Some(scale) = motion_vector_scale
+        && scale.iter().any(|s| !s.is_finite() || *s == 0.0)
+    {
+        return Err(DlssError::MotionVectorMismatch);
+    }
+
+    let covers_upscaled_resolution = motion_vectors_size[0] >= upscaled_resolution[0]
+        && motion_vectors_size[1] >= upscaled_resolution[1];
+    let color_is_upscaled =
+        color_size[0] >= upscaled_resolution[0] && color_size[1] >= upscaled_resolution[1];
+    let low_resolution = feature_flags.contains(DlssFeatureFlags::LowResolutionMotionVectors);
+    let mismatch = if low_resolution {
+        covers_upscaled_resolution && !color_is_upscaled
+    } else {
+        !covers_upscaled_resolution
+    };
+    if mismatch {
+        return Err(DlssError::MotionVectorMismatch);
     }
+    Ok(())
 }
 
-pub fn texture_to_ngx(texture_view: &TextureView, adapter: &Adapter) -> NVSDK_NGX_Resource_VK {
+/// The array layers of a texture that a texture view passed to DLSS covers, e.g. one eye of an OpenXR array swapchain.
+///
+/// Must match the `base_array_layer` and `array_layer_count` the view was created with, which wgpu does not expose.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct DlssArrayLayers {
+    pub base_array_layer: u32,
+    /// `None` covers every layer from `base_array_layer` onwards.
+    pub array_layer_count: Option<u32>,
+}
+
+impl DlssArrayLayers {
+    /// A view of the single array layer `layer`.
+    pub fn single(layer: u32) -> Self {
+        Self {
+            base_array_layer: layer,
+            array_layer_count: Some(1),
+        }
+    }
+}
+
+pub fn texture_to_ngx(
+    texture_view: &TextureView,
+    array_layers: DlssArrayLayers,
+    adapter: &Adapter,
+) -> NVSDK_NGX_Resource_VK {
     unsafe {
         let raw_view = texture_view.as_hal::<Vulkan>().unwrap().raw_handle();
         let texture = texture_view.texture();
@@ -215,8 +324,10 @@ pub fn texture_to_ngx(texture_view: &TextureView, adapter: &Adapter) -> NVSDK_NG
                 },
                 base_mip_level: 0,
                 level_count: REMAINING_MIP_LEVELS,
-                base_array_layer: 0,
-                layer_count: REMAINING_ARRAY_LAYERS,
+                base_array_layer: array_layers.base_array_layer,
+                layer_count: array_layers
+                    .array_layer_count
+                    .unwrap_or(REMAINING_ARRAY_LAYERS),
             },
             adapter
                 .as_hal::<Vulkan>()
@@ -229,13 +340,174 @@ pub fn texture_to_ngx(texture_view: &TextureView, adapter: &Adapter) -> NVSDK_NG
     }
 }
 
-pub fn halton_sequence(mut index: u32, base: u32) -> f32 {
-    let mut f = 1.0;
-    let mut result = 0.0;
-    while index > 0 {
-        f /= base as f32;
-        result += f * (index % base) as f32;
-        index = (index as f32 / base as f32).floor() as u32;
+pub fn optional_resource(
+    resource: &mut Option<NVSDK_NGX_Resource_VK>,
+) -> *mut NVSDK_NGX_Resource_VK {
+    match resource {
+        Some(resource) => resource,
+        None => ptr::null_mut(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPSCALED: [u32; 2] = [1920, 1080];
+    const RENDER: [u32; 2] = [1280, 720];
+
+    fn check(feature_flags: DlssFeatureFlags, color: [u32; 2], motion_vectors: [u32; 2]) -> bool {
+        check_motion_vectors(feature_flags, UPSCALED, color, motion_vectors, None).is_ok()
+    }
+
+    #[test]
+    fn upscaled_motion_vectors() {
+        let flags = DlssFeatureFlags::empty();
+        assert!(check(flags, RENDER, UPSCALED));
+        assert!(!check(flags, RENDER, RENDER));
+        // DLAA, and full size textures used with partial_texture_size
+        assert!(check(flags, UPSCALED, UPSCALED));
+    }
+
+    #[test]
+    fn low_resolution_motion_vectors() {
+        let flags = DlssFeatureFlags::LowResolutionMotionVectors;
+        assert!(check(flags, RENDER, RENDER));
+        assert!(!check(flags, RENDER, UPSCALED));
+        // DLAA
+        assert!(check(flags, UPSCALED, UPSCALED));
+    }
+
+    #[test]
+    fn unknown_ngx_result() {
+        assert_eq!(try_check_ngx_result(NVSDK_NGX_Result_Success), Some(Ok(())));
+        assert_eq!(
+            try_check_ngx_result(NVSDK_NGX_Result_FAIL_FeatureNotSupported),
+            Some(Err(DlssError::FeatureNotSupported))
+        );
+        assert_eq!(try_check_ngx_result(NVSDK_NGX_Result_Fail), None);
+    }
+
+    #[test]
+    fn error_codes() {
+        let errors = [
+            DlssError::FeatureNotSupported,
+            DlssError::PlatformError,
+            DlssError::FeatureAlreadyExists,
+            DlssError::FeatureNotFound,
+            DlssError::InvalidParameters,
+            DlssError::ScratchBufferTooSmall,
+            DlssError::NotInitialized,
+            DlssError::UnsupportedInputFormat,
+            DlssError::RWFlagMissing,
+            DlssError::MissingInput,
+            DlssError::UnableToInitializeFeature,
+            DlssError::OutOfDate,
+            DlssError::OutOfGPUMemory,
+            DlssError::UnsupportedFormat,
+            DlssError::UnableToWriteToAppDataPath,
+            DlssError::UnsupportedParameter,
+            DlssError::Denied,
+            DlssError::NotImplemented,
+            DlssError::SdkPoisoned,
+            DlssError::AlreadyInitialized,
+            DlssError::SdkReinitialized,
+            DlssError::SrgbOutput,
+            DlssError::DeviceMismatch,
+            DlssError::SurfaceNotConfigured,
+            DlssError::MotionVectorMismatch,
+        ];
+        for (code, error) in (1..).zip(errors) {
+            assert_eq!(error.code(), code, "{error:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_motion_vector_scale() {
+        for scale in [[0.0, 1.0], [1.0, f32::NAN], [f32::INFINITY, 1.0]] {
+            assert!(
+                check_motion_vectors(
+                    DlssFeatureFlags::empty(),
+                    UPSCALED,
+                    RENDER,
+                    UPSCALED,
+                    Some(scale),
+                )
+                .is_err()
+            );
+        }
+    }
+
+    /// A column-major perspective projection with depth `a * z + b` and `w = w_sign * z`, as in `glam`'s `perspective_*` functions.
+    fn perspective(w_sign: f32, a: f32, b: f32) -> [f32; 16] {
+        let mut view_to_clip = [0.0; 16];
+        view_to_clip[0] = 1.0;
+        view_to_clip[5] = 1.0;
+        view_to_clip[10] = a;
+        view_to_clip[11] = w_sign;
+        view_to_clip[14] = b;
+        view_to_clip
+    }
+
+    /// A column-major orthographic projection looking down -Z, mapping `near..far` to depth `0..1`.
+    fn orthographic(near: f32, far: f32) -> [f32; 16] {
+        let mut view_to_clip = [0.0; 16];
+        view_to_clip[0] = 1.0;
+        view_to_clip[5] = 1.0;
+        view_to_clip[10] = 1.0 / (near - far);
+        view_to_clip[14] = near / (near - far);
+        view_to_clip[15] = 1.0;
+        view_to_clip
+    }
+
+    #[test]
+    fn inverted_depth() {
+        let (near, far) = (0.1, 1000.0);
+        let cases = [
+            // Right-handed, w = -z
+            (
+                perspective(-1.0, far / (near - far), near * far / (near - far)),
+                false,
+            ),
+            (
+                perspective(-1.0, near / (far - near), near * far / (far - near)),
+                true,
+            ),
+            // Infinite far plane
+            (perspective(-1.0, -1.0, -near), false),
+            (perspective(-1.0, 0.0, near), true),
+            // Left-handed, w = z
+            (
+                perspective(1.0, far / (far - near), -near * far / (far - near)),
+                false,
+            ),
+            (
+                perspective(1.0, near / (near - far), near * far / (far - near)),
+                true,
+            ),
+            (perspective(1.0, 0.0, near), true),
+            // Orthographic, with the near and far planes swapped for reverse-Z
+            (orthographic(near, far), false),
+            (orthographic(far, near), true),
+        ];
+        for (view_to_clip, expected) in cases {
+            // Check the test matrices themselves, using points in front of the camera
+            let depth = |distance: f32| {
+                let z = if view_to_clip[11] > 0.0 {
+                    distance
+                } else {
+                    -distance
+                };
+                let w = view_to_clip[11] * z + view_to_clip[15];
+                (view_to_clip[10] * z + view_to_clip[14]) / w
+            };
+            assert_eq!(depth(10.0) < depth(1.0), expected, "{view_to_clip:?}");
+
+            assert_eq!(
+                DlssFeatureFlags::projection_has_inverted_depth(view_to_clip),
+                expected,
+                "{view_to_clip:?}"
+            );
+        }
     }
-    result
 }
@@ -0,0 +1,92 @@
+use std::{collections::HashMap, hash::Hash};
+use wgpu::{
+    Device, Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    TextureView,
+};
+
+/// Hands out DLSS output textures per camera, recycling them as cameras resize, change format, or are removed.
+///
+/// Useful for editors with many short-lived viewports, to avoid allocating a new output texture for every resize.
+///
+/// `K` identifies a camera, e.g. an entity ID.
+///
+/// Textures are created with [`TextureUsages::STORAGE_BINDING`], [`TextureUsages::TEXTURE_BINDING`], and [`TextureUsages::COPY_SRC`],
+/// plus any extra usages passed to [`Self::new`].
+pub struct DlssOutputTexturePool<K> {
+    device: Device,
+    usage: TextureUsages,
+    cameras: HashMap<K, TextureView>,
+    free: Vec<TextureView>,
+}
+
+impl<K: Hash + Eq> DlssOutputTexturePool<K> {
+    /// Create a new, empty [`DlssOutputTexturePool`].
+    pub fn new(device: &Device, extra_usages: TextureUsages) -> Self {
+        Self {
+            device: device.clone(),
+            usage: TextureUsages::STORAGE_BINDING
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC
+                | extra_usages,
+            cameras: HashMap::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Get the output texture for `camera`, for use as [`crate::super_resolution::DlssSuperResolutionRenderParameters::dlss_output`].
+    ///
+    /// The camera's previous texture is reused if it matches, and is otherwise returned to the pool, which is searched
+    /// for a matching texture before creating a new one. Use [`TextureView::texture`] to get the texture itself.
+    pub fn acquire(
+        &mut self,
+        camera: K,
+        upscaled_resolution: [u32; 2],
+        format: TextureFormat,
+    ) -> &TextureView {
+        let size = Extent3d {
+            width: upscaled_resolution[0],
+            height: upscaled_resolution[1],
+            depth_or_array_layers: 1,
+        };
+        let matches =
+            |view: &TextureView| view.texture().size() == size && view.texture().format() == format;
+
+        if self.cameras.get(&camera).is_some_and(|view| !matches(view)) {
+            self.free.extend(self.cameras.remove(&camera));
+        }
+
+        self.cameras
+            .entry(camera)
+            .or_insert_with(|| match self.free.iter().position(matches) {
+                Some(index) => self.free.swap_remove(index),
+                None => self
+                    .device
+                    .create_texture(&TextureDescriptor {
+                        label: Some("dlss_output"),
+                        size,
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: TextureDimension::D2,
+                        format,
+                        usage: self.usage,
+                        view_formats: &[],
+                    })
+                    .create_view(&Default::default()),
+            })
+    }
+
+    /// Return the texture of `camera` to the pool, e.g. when its viewport is closed.
+    pub fn release(&mut self, camera: &K) {
+        self.free.extend(self.cameras.remove(camera));
+    }
+
+    /// Free all textures in the pool that are not held by a camera.
+    pub fn trim(&mut self) {
+        self.free.clear();
+    }
+
+    /// Number of textures in the pool that are not held by a camera.
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+}
@@ -0,0 +1,80 @@
+use wgpu::{Texture, TextureTransition, TextureUses};
+
+/// The textures a DLSS evaluation reads and writes, and the states they must be in,
+/// for declaring the dependencies of a DLSS node in a render graph.
+///
+/// Rendering transitions every texture into its required state, so a render graph only needs to order the DLSS node
+/// after the passes writing its reads, and before the passes reading its writes.
+#[derive(Clone, Debug)]
+pub struct DlssPassDescriptor<'a> {
+    /// Textures sampled by DLSS.
+    pub reads: Vec<DlssPassResource<'a>>,
+    /// Textures written by DLSS.
+    pub writes: Vec<DlssPassResource<'a>>,
+}
+
+/// A texture accessed by a DLSS evaluation. See [`DlssPassDescriptor`].
+#[derive(Clone, Copy, Debug)]
+pub struct DlssPassResource<'a> {
+    /// Which render parameter the texture was passed as.
+    pub role: DlssPassRole,
+    pub texture: &'a Texture,
+    /// The state DLSS requires the texture to be in.
+    pub state: TextureUses,
+}
+
+/// The render parameter a [`DlssPassResource`] was passed as.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DlssPassRole {
+    Color,
+    Depth,
+    MotionVectors,
+    Exposure,
+    Bias,
+    DiffuseAlbedo,
+    SpecularAlbedo,
+    Normals,
+    Roughness,
+    SpecularMotionVectors,
+    SpecularHitDistance,
+    ScreenSpaceSubsurfaceScatteringGuide,
+    Output,
+}
+
+impl<'a> DlssPassDescriptor<'a> {
+    pub(crate) fn new(
+        reads: impl IntoIterator<Item = Option<(DlssPassRole, &'a Texture)>>,
+        output: &'a Texture,
+    ) -> Self {
+        Self {
+            reads: reads
+                .into_iter()
+                .flatten()
+                .map(|(role, texture)| DlssPassResource {
+                    role,
+                    texture,
+                    state: TextureUses::RESOURCE,
+                })
+                .collect(),
+            writes: vec![DlssPassResource {
+                role: DlssPassRole::Output,
+                texture: output,
+                state: TextureUses::STORAGE_READ_WRITE,
+            }],
+        }
+    }
+
+    /// Transitions of every texture into its required state.
+    pub fn transitions(&self) -> impl Iterator<Item = TextureTransition<&'a Texture>> + use<'a> {
+        self.reads
+            .iter()
+            .chain(&self.writes)
+            .map(|resource| TextureTransition {
+                texture: resource.texture,
+                selector: None,
+                state: resource.state,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
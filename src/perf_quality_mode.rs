@@ -0,0 +1,263 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+/// How much DLSS should upscale by.
+///
+/// Implements [`Display`] and [`FromStr`] for config files and console commands, using `snake_case` names such as
+/// `ultra_performance`, or the ratio for [`Self::Custom`], e.g. `0.75`.
+#[derive(Clone, Copy, Default, Debug)]
+pub enum DlssPerfQualityMode {
+    /// Let DLSS decide.
+    #[default]
+    Auto,
+    /// Anti-aliasing only, no upscaling.
+    Dlaa,
+    Quality,
+    Balanced,
+    Performance,
+    UltraPerformance,
+    /// A custom ratio of render resolution to upscaled resolution, per axis, e.g. `0.75` for a 75% render scale.
+    ///
+    /// DLSS is configured using the preset closest to the ratio, and the render resolution is clamped to the range DLSS supports for that preset.
+    /// Ratios below 1.0 never use [`Self::Dlaa`], which only supports rendering at the upscaled resolution.
+    ///
+    /// The ratio must be positive and finite. Other ratios are printed by [`Display`], but rejected by [`FromStr`].
+    Custom(f32),
+}
+
+impl DlssPerfQualityMode {
+    /// Resolves [`Self::Auto`] and [`Self::Custom`] to a specific preset mode based on the upscaled resolution.
+    pub(crate) fn resolve(&self, upscaled_resolution: [u32; 2]) -> Self {
+        match self {
+            Self::Auto => {
+                let mega_pixels =
+                    (upscaled_resolution[0] * upscaled_resolution[1]) as f32 / 1_000_000.0;
+
+                if mega_pixels < 2.03 {
+                    Self::Dlaa
+                } else if mega_pixels < 3.68 {
+                    Self::Quality
+                } else if mega_pixels < 8.29 {
+                    Self::Performance
+                } else {
+                    Self::UltraPerformance
+                }
+            }
+            Self::Custom(ratio) if *ratio >= 1.0 => Self::Dlaa,
+            Self::Custom(ratio) => [
+                Self::Quality,
+                Self::Balanced,
+                Self::Performance,
+                Self::UltraPerformance,
+            ]
+            .into_iter()
+            .min_by(|a, b| {
+                (a.render_scale() - ratio)
+                    .abs()
+                    .total_cmp(&(b.render_scale() - ratio).abs())
+            })
+            .unwrap(),
+            mode => *mode,
+        }
+    }
+
+    /// The nominal ratio of render resolution to upscaled resolution, per axis.
+    ///
+    /// Must not be called on [`Self::Auto`].
+    pub(crate) fn render_scale(&self) -> f32 {
+        match self {
+            Self::Auto => unreachable!(),
+            Self::Dlaa => 1.0,
+            Self::Quality => 1.0 / 1.5,
+            Self::Balanced => 0.58,
+            Self::Performance => 0.5,
+            Self::UltraPerformance => 1.0 / 3.0,
+            Self::Custom(ratio) => *ratio,
+        }
+    }
+
+    /// The nominal ratio of render resolution to upscaled resolution per axis, e.g. for display in settings menus.
+    ///
+    /// Does not require DLSS to be initialized. Returns `None` for [`Self::Auto`], which depends on the upscaled resolution.
+    pub fn nominal_render_scale(&self) -> Option<f32> {
+        match self {
+            Self::Auto => None,
+            mode => Some(mode.render_scale()),
+        }
+    }
+
+    /// The approximate render resolution for `upscaled_resolution`, based on [`Self::nominal_render_scale`].
+    ///
+    /// Does not require DLSS to be initialized. The actual render resolution chosen by DLSS may differ slightly.
+    pub fn approximate_render_resolution(&self, upscaled_resolution: [u32; 2]) -> [u32; 2] {
+        let scale = match self {
+            Self::Custom(ratio) => ratio.clamp(1.0 / 3.0, 1.0),
+            mode => mode.resolve(upscaled_resolution).render_scale(),
+        };
+        upscaled_resolution.map(|size| ((size as f32 * scale).round() as u32).max(1))
+    }
+
+    /// For [`Self::Custom`], the render resolution for the custom ratio, clamped between `min_render_resolution` and `max_render_resolution`.
+    #[cfg(not(feature = "mock"))]
+    pub(crate) fn custom_render_resolution(
+        &self,
+        upscaled_resolution: [u32; 2],
+        min_render_resolution: [u32; 2],
+        max_render_resolution: [u32; 2],
+    ) -> Option<[u32; 2]> {
+        let Self::Custom(ratio) = self else {
+            return None;
+        };
+
+        Some([0, 1].map(|axis| {
+            // NGX does not guarantee that min <= max, and u32::clamp panics otherwise
+            let (min, max) = (min_render_resolution[axis], max_render_resolution[axis]);
+            ((upscaled_resolution[axis] as f32 * ratio).round() as u32)
+                .clamp(min.min(max), max.max(min))
+        }))
+    }
+}
+
+impl PartialEq for DlssPerfQualityMode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => a.to_bits() == b.to_bits(),
+            (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+        }
+    }
+}
+
+impl Eq for DlssPerfQualityMode {}
+
+impl Hash for DlssPerfQualityMode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Self::Custom(ratio) = self {
+            ratio.to_bits().hash(state);
+        }
+    }
+}
+
+impl Display for DlssPerfQualityMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => f.write_str("auto"),
+            Self::Dlaa => f.write_str("dlaa"),
+            Self::Quality => f.write_str("quality"),
+            Self::Balanced => f.write_str("balanced"),
+            Self::Performance => f.write_str("performance"),
+            Self::UltraPerformance => f.write_str("ultra_performance"),
+            Self::Custom(ratio) => write!(f, "{ratio}"),
+        }
+    }
+}
+
+impl FromStr for DlssPerfQualityMode {
+    type Err = DlssParseError;
+
+    /// Parses names case-insensitively, accepting `-` in place of `_`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize(s).as_str() {
+            "auto" => Ok(Self::Auto),
+            "dlaa" => Ok(Self::Dlaa),
+            "quality" => Ok(Self::Quality),
+            "balanced" => Ok(Self::Balanced),
+            "performance" => Ok(Self::Performance),
+            "ultra_performance" => Ok(Self::UltraPerformance),
+            ratio => match ratio.parse::<f32>() {
+                Ok(ratio) if ratio > 0.0 && ratio.is_finite() => Ok(Self::Custom(ratio)),
+                _ => Err(DlssParseError(s.to_owned())),
+            },
+        }
+    }
+}
+
+/// Error returned when parsing a DLSS settings enum, such as [`DlssPerfQualityMode`], from an unrecognized string.
+#[derive(thiserror::Error, Clone, PartialEq, Eq, Debug)]
+#[error("Unrecognized DLSS setting {0:?}")]
+pub struct DlssParseError(pub String);
+
+/// Lowercases `s` and replaces `-` with `_`, for lenient parsing of settings enums.
+pub(crate) fn normalize(s: &str) -> String {
+    s.trim().to_ascii_lowercase().replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trip() {
+        for mode in [
+            DlssPerfQualityMode::Auto,
+            DlssPerfQualityMode::Dlaa,
+            DlssPerfQualityMode::Quality,
+            DlssPerfQualityMode::Balanced,
+            DlssPerfQualityMode::Performance,
+            DlssPerfQualityMode::UltraPerformance,
+            DlssPerfQualityMode::Custom(0.75),
+            DlssPerfQualityMode::Custom(1.0 / 3.0),
+            DlssPerfQualityMode::Custom(1.0),
+            DlssPerfQualityMode::Custom(1.5),
+        ] {
+            assert_eq!(mode.to_string().parse(), Ok(mode), "{mode}");
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            " Ultra-Performance".parse(),
+            Ok(DlssPerfQualityMode::UltraPerformance)
+        );
+        assert_eq!("DLAA".parse(), Ok(DlssPerfQualityMode::Dlaa));
+        assert_eq!("0.5".parse(), Ok(DlssPerfQualityMode::Custom(0.5)));
+        for invalid in ["", "ultra", "0", "-0.5", "nan", "inf"] {
+            assert_eq!(
+                invalid.parse::<DlssPerfQualityMode>(),
+                Err(DlssParseError(invalid.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_custom() {
+        let upscaled_resolution = [3840, 2160];
+        for (ratio, expected) in [
+            (1.0, DlssPerfQualityMode::Dlaa),
+            (1.5, DlssPerfQualityMode::Dlaa),
+            // Not DLAA, which would force rendering at the upscaled resolution
+            (0.95, DlssPerfQualityMode::Quality),
+            (0.7, DlssPerfQualityMode::Quality),
+            (0.56, DlssPerfQualityMode::Balanced),
+            (0.5, DlssPerfQualityMode::Performance),
+            (0.2, DlssPerfQualityMode::UltraPerformance),
+        ] {
+            assert_eq!(
+                DlssPerfQualityMode::Custom(ratio).resolve(upscaled_resolution),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "mock"))]
+    fn custom_render_resolution_bounds() {
+        let mode = DlssPerfQualityMode::Custom(0.9);
+        assert_eq!(
+            mode.custom_render_resolution([1000, 1000], [500, 500], [800, 800]),
+            Some([800, 800])
+        );
+        assert_eq!(
+            mode.custom_render_resolution([1000, 1000], [800, 800], [500, 500]),
+            Some([800, 800])
+        );
+        assert_eq!(
+            DlssPerfQualityMode::Quality.custom_render_resolution([1000, 1000], [0, 0], [0, 0]),
+            None
+        );
+    }
+}
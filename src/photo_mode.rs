@@ -0,0 +1,93 @@
+use crate::{
+    DlssError, DlssPerfQualityMode,
+    super_resolution::{DlssSuperResolution, DlssSuperResolutionRenderParameters},
+};
+use std::sync::Arc;
+use wgpu::{Adapter, CommandBuffer, CommandEncoder, Device, Queue};
+
+/// Temporarily upscales a camera at a higher quality mode, such as DLAA, for screenshots.
+///
+/// Wraps the camera's existing context, which is kept alive and restored by [`Self::finish`].
+/// Render with [`Self::render`], at [`Self::context`]'s render resolution, until [`Self::is_converged`] is true,
+/// and then capture the output.
+pub struct DlssPhotoMode {
+    previous: DlssSuperResolution,
+    photo: DlssSuperResolution,
+    frames_rendered: u32,
+    frames_to_converge: u32,
+}
+
+impl DlssPhotoMode {
+    /// Create a photo mode context with the same upscaled resolution and feature flags as `context`, but using `perf_quality_mode`,
+    /// typically [`DlssPerfQualityMode::Dlaa`].
+    ///
+    /// The output is considered converged after `frames_to_converge` frames of a static scene.
+    ///
+    /// On failure, `context` is returned alongside the error.
+    #[allow(clippy::result_large_err)]
+    pub fn new(
+        context: DlssSuperResolution,
+        perf_quality_mode: DlssPerfQualityMode,
+        frames_to_converge: u32,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Self, (DlssError, DlssSuperResolution)> {
+        let photo = DlssSuperResolution::new(
+            context.upscaled_resolution(),
+            perf_quality_mode,
+            context.feature_flags(),
+            Arc::clone(context.sdk()),
+            device,
+            queue,
+        );
+        match photo {
+            Ok(photo) => Ok(Self {
+                previous: context,
+                photo,
+                frames_rendered: 0,
+                frames_to_converge,
+            }),
+            Err(err) => Err((err, context)),
+        }
+    }
+
+    /// Encode rendering commands for the photo mode context. See [`DlssSuperResolution::render`].
+    ///
+    /// Passing `reset` restarts convergence, e.g. if the scene changed.
+    pub fn render(
+        &mut self,
+        render_parameters: DlssSuperResolutionRenderParameters,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        let reset = render_parameters.reset;
+        let command_buffer = self
+            .photo
+            .render(render_parameters, command_encoder, adapter)?;
+        self.frames_rendered = if reset {
+            1
+        } else {
+            self.frames_rendered.saturating_add(1)
+        };
+        Ok(command_buffer)
+    }
+
+    /// Whether enough frames have been accumulated since the last reset for the output to be captured.
+    pub fn is_converged(&self) -> bool {
+        self.frames_rendered >= self.frames_to_converge
+    }
+
+    /// The photo mode context. Use it to query the render resolution and suggested jitter.
+    pub fn context(&self) -> &DlssSuperResolution {
+        &self.photo
+    }
+
+    /// Destroy the photo mode context and return the camera's original context.
+    ///
+    /// The original context's history is reset, as it did not see the frames rendered in photo mode.
+    pub fn finish(self) -> DlssSuperResolution {
+        let mut previous = self.previous;
+        previous.request_reset();
+        previous
+    }
+}
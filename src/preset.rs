@@ -0,0 +1,169 @@
+use crate::{DlssPerfQualityMode, nvsdk_ngx::*};
+
+/// Which DLSS Super Resolution model to use, overriding the driver's choice.
+///
+/// See `$DLSS_SDK/doc/DLSS_Programming_Guide_Release.pdf` for the properties of each preset.
+/// Presets A-E have been removed from the SDK.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DlssSuperResolutionPreset {
+    /// Let the driver decide.
+    #[default]
+    Default,
+    /// CNN model, for Ultra Performance and DLAA.
+    F,
+    /// Transformer model.
+    J,
+    /// Transformer model, the default for most modes.
+    K,
+    /// Transformer model, for Ultra Performance.
+    L,
+    /// Transformer model, for Performance.
+    M,
+}
+
+/// Which DLSS Ray Reconstruction model to use, overriding the driver's choice.
+///
+/// See `$DLSS_SDK/doc/DLSS-RR Integration Guide.pdf` for the properties of each preset.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DlssRayReconstructionPreset {
+    /// Let the driver decide.
+    #[default]
+    Default,
+    /// Transformer model.
+    D,
+    /// Transformer model, with improved stability.
+    E,
+}
+
+/// A render preset for each [`DlssPerfQualityMode`], matching the per-mode preset hints of NGX.
+///
+/// [`DlssPerfQualityMode::Auto`] and [`DlssPerfQualityMode::Custom`] use the preset of the mode they resolve to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct DlssRenderPresets<P> {
+    pub dlaa: P,
+    pub quality: P,
+    pub balanced: P,
+    pub performance: P,
+    pub ultra_performance: P,
+}
+
+impl<P: Copy> DlssRenderPresets<P> {
+    /// Use `preset` for every mode.
+    pub fn all(preset: P) -> Self {
+        Self {
+            dlaa: preset,
+            quality: preset,
+            balanced: preset,
+            performance: preset,
+            ultra_performance: preset,
+        }
+    }
+
+    /// The preset used for `perf_quality_mode` at `upscaled_resolution`.
+    pub fn get(&self, perf_quality_mode: DlssPerfQualityMode, upscaled_resolution: [u32; 2]) -> P {
+        match perf_quality_mode.resolve(upscaled_resolution) {
+            DlssPerfQualityMode::Auto | DlssPerfQualityMode::Custom(_) => unreachable!(),
+            DlssPerfQualityMode::Dlaa => self.dlaa,
+            DlssPerfQualityMode::Quality => self.quality,
+            DlssPerfQualityMode::Balanced => self.balanced,
+            DlssPerfQualityMode::Performance => self.performance,
+            DlssPerfQualityMode::UltraPerformance => self.ultra_performance,
+        }
+    }
+}
+
+impl DlssSuperResolutionPreset {
+    fn value(self) -> NVSDK_NGX_DLSS_Hint_Render_Preset {
+        match self {
+            Self::Default => {
+                NVSDK_NGX_DLSS_Hint_Render_Preset_NVSDK_NGX_DLSS_Hint_Render_Preset_Default
+            }
+            Self::F => NVSDK_NGX_DLSS_Hint_Render_Preset_NVSDK_NGX_DLSS_Hint_Render_Preset_F,
+            Self::J => NVSDK_NGX_DLSS_Hint_Render_Preset_NVSDK_NGX_DLSS_Hint_Render_Preset_J,
+            Self::K => NVSDK_NGX_DLSS_Hint_Render_Preset_NVSDK_NGX_DLSS_Hint_Render_Preset_K,
+            Self::L => NVSDK_NGX_DLSS_Hint_Render_Preset_NVSDK_NGX_DLSS_Hint_Render_Preset_L,
+            Self::M => NVSDK_NGX_DLSS_Hint_Render_Preset_NVSDK_NGX_DLSS_Hint_Render_Preset_M,
+        }
+    }
+}
+
+impl DlssRenderPresets<DlssSuperResolutionPreset> {
+    /// Sets the preset hints on `parameters`, to be read when the next DLSS Super Resolution feature is created.
+    pub(crate) unsafe fn apply(&self, parameters: *mut NVSDK_NGX_Parameter) {
+        for (name, preset) in [
+            (NVSDK_NGX_Parameter_DLSS_Hint_Render_Preset_DLAA, self.dlaa),
+            (
+                NVSDK_NGX_Parameter_DLSS_Hint_Render_Preset_Quality,
+                self.quality,
+            ),
+            (
+                NVSDK_NGX_Parameter_DLSS_Hint_Render_Preset_Balanced,
+                self.balanced,
+            ),
+            (
+                NVSDK_NGX_Parameter_DLSS_Hint_Render_Preset_Performance,
+                self.performance,
+            ),
+            (
+                NVSDK_NGX_Parameter_DLSS_Hint_Render_Preset_UltraPerformance,
+                self.ultra_performance,
+            ),
+            (
+                NVSDK_NGX_Parameter_DLSS_Hint_Render_Preset_UltraQuality,
+                self.quality,
+            ),
+        ] {
+            unsafe { NVSDK_NGX_Parameter_SetUI(parameters, name.as_ptr().cast(), preset.value()) };
+        }
+    }
+}
+
+impl DlssRayReconstructionPreset {
+    fn value(self) -> NVSDK_NGX_RayReconstruction_Hint_Render_Preset {
+        match self {
+            Self::Default => {
+                NVSDK_NGX_RayReconstruction_Hint_Render_Preset_NVSDK_NGX_RayReconstruction_Hint_Render_Preset_Default
+            }
+            Self::D => {
+                NVSDK_NGX_RayReconstruction_Hint_Render_Preset_NVSDK_NGX_RayReconstruction_Hint_Render_Preset_D
+            }
+            Self::E => {
+                NVSDK_NGX_RayReconstruction_Hint_Render_Preset_NVSDK_NGX_RayReconstruction_Hint_Render_Preset_E
+            }
+        }
+    }
+}
+
+impl DlssRenderPresets<DlssRayReconstructionPreset> {
+    /// Sets the preset hints on `parameters`, to be read when the next DLSS Ray Reconstruction feature is created.
+    pub(crate) unsafe fn apply(&self, parameters: *mut NVSDK_NGX_Parameter) {
+        for (name, preset) in [
+            (
+                NVSDK_NGX_Parameter_RayReconstruction_Hint_Render_Preset_DLAA,
+                self.dlaa,
+            ),
+            (
+                NVSDK_NGX_Parameter_RayReconstruction_Hint_Render_Preset_Quality,
+                self.quality,
+            ),
+            (
+                NVSDK_NGX_Parameter_RayReconstruction_Hint_Render_Preset_Balanced,
+                self.balanced,
+            ),
+            (
+                NVSDK_NGX_Parameter_RayReconstruction_Hint_Render_Preset_Performance,
+                self.performance,
+            ),
+            (
+                NVSDK_NGX_Parameter_RayReconstruction_Hint_Render_Preset_UltraPerformance,
+                self.ultra_performance,
+            ),
+            (
+                NVSDK_NGX_Parameter_RayReconstruction_Hint_Render_Preset_UltraQuality,
+                self.quality,
+            ),
+        ] {
+            unsafe { NVSDK_NGX_Parameter_SetUI(parameters, name.as_ptr().cast(), preset.value()) };
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::DlssPerfQualityMode;
+use std::time::Duration;
+
+/// Preset modes, ordered from highest quality to highest performance.
+const MODES: [DlssPerfQualityMode; 5] = [
+    DlssPerfQualityMode::Dlaa,
+    DlssPerfQualityMode::Quality,
+    DlssPerfQualityMode::Balanced,
+    DlssPerfQualityMode::Performance,
+    DlssPerfQualityMode::UltraPerformance,
+];
+
+/// Automatically steps [`DlssPerfQualityMode`] up or down to hit a target GPU frame time.
+///
+/// Measure the GPU frame time yourself, e.g. using [`wgpu::Features::TIMESTAMP_QUERY`], and pass it to [`Self::update`] once per frame.
+/// When the mode changes, recreate your DLSS context with the new mode.
+///
+/// Optionally also pass VRAM usage to [`Self::update_memory_usage`] once per frame, to step down before running out of memory.
+pub struct DlssQualityController {
+    target_frame_time: Duration,
+    highest_quality: usize,
+    highest_performance: usize,
+    current: usize,
+    hysteresis_frames: u32,
+    frames_over_budget: u32,
+    frames_under_budget: u32,
+    frames_over_memory_budget: u32,
+    memory_pressure: bool,
+}
+
+impl DlssQualityController {
+    /// Create a new [`DlssQualityController`], starting at `highest_quality_mode`.
+    ///
+    /// The mode will not change until the frame time has been over or under budget for `hysteresis_frames` consecutive frames.
+    ///
+    /// # Panics
+    /// Panics if either mode is [`DlssPerfQualityMode::Auto`] or [`DlssPerfQualityMode::Custom`],
+    /// if `highest_quality_mode` is a higher performance mode than `highest_performance_mode`, or if `hysteresis_frames` is 0.
+    pub fn new(
+        target_frame_time: Duration,
+        highest_quality_mode: DlssPerfQualityMode,
+        highest_performance_mode: DlssPerfQualityMode,
+        hysteresis_frames: u32,
+    ) -> Self {
+        let highest_quality = mode_index(highest_quality_mode);
+        let highest_performance = mode_index(highest_performance_mode);
+        assert!(
+            highest_quality <= highest_performance,
+            "{highest_quality_mode:?} is a higher performance mode than {highest_performance_mode:?}"
+        );
+        assert!(
+            hysteresis_frames > 0,
+            "Expected hysteresis_frames to be at least 1"
+        );
+
+        Self {
+            target_frame_time,
+            highest_quality,
+            highest_performance,
+            current: highest_quality,
+            hysteresis_frames,
+            frames_over_budget: 0,
+            frames_under_budget: 0,
+            frames_over_memory_budget: 0,
+            memory_pressure: false,
+        }
+    }
+
+    /// Record the GPU frame time of the latest frame.
+    ///
+    /// Returns the new mode if it changed, in which case the DLSS context should be recreated.
+    pub fn update(&mut self, gpu_frame_time: Duration) -> Option<DlssPerfQualityMode> {
+        // Only step up in quality with enough headroom that the next mode is unlikely to immediately go over budget,
+        // and count frames towards stepping up only once VRAM usage is below the threshold
+        if gpu_frame_time > self.target_frame_time {
+            self.frames_over_budget += 1;
+            self.frames_under_budget = 0;
+        } else if gpu_frame_time < self.target_frame_time.mul_f32(0.75) && !self.memory_pressure {
+            self.frames_under_budget += 1;
+            self.frames_over_budget = 0;
+        } else {
+            self.frames_over_budget = 0;
+            self.frames_under_budget = 0;
+        }
+
+        let previous = self.current;
+        if self.frames_over_budget >= self.hysteresis_frames {
+            self.current = (self.current + 1).min(self.highest_performance);
+        } else if self.frames_under_budget >= self.hysteresis_frames {
+            self.current = self.current.saturating_sub(1).max(self.highest_quality);
+        } else {
+            return None;
+        }
+
+        self.frames_over_budget = 0;
+        self.frames_under_budget = 0;
+        (self.current != previous).then(|| self.mode())
+    }
+
+    /// Record the VRAM usage of the latest frame, as a percentage of the budget, e.g. from [`crate::DlssMemoryUsage::percentage`].
+    ///
+    /// While usage is at or above `threshold_percentage`, the mode steps down to higher performance modes, which need less VRAM,
+    /// waiting `hysteresis_frames` frames between steps for replaced contexts to be freed. [`Self::update`] will not step up
+    /// in quality until usage drops below the threshold.
+    ///
+    /// `threshold_percentage` should be below [`wgpu::MemoryBudgetThresholds::for_resource_creation`], if set.
+    ///
+    /// Returns the new mode if it changed, in which case the DLSS context should be recreated.
+    pub fn update_memory_usage(
+        &mut self,
+        usage_percentage: u8,
+        threshold_percentage: u8,
+    ) -> Option<DlssPerfQualityMode> {
+        self.memory_pressure = usage_percentage >= threshold_percentage;
+        if !self.memory_pressure {
+            self.frames_over_memory_budget = 0;
+            return None;
+        }
+
+        // Step down on the first frame over budget, and then every hysteresis_frames frames after that
+        let frames_over_memory_budget = self.frames_over_memory_budget;
+        self.frames_over_memory_budget = (frames_over_memory_budget + 1) % self.hysteresis_frames;
+        if frames_over_memory_budget != 0 {
+            return None;
+        }
+
+        let previous = self.current;
+        self.current = (self.current + 1).min(self.highest_performance);
+        self.frames_over_budget = 0;
+        self.frames_under_budget = 0;
+        (self.current != previous).then(|| self.mode())
+    }
+
+    /// The currently selected mode.
+    pub fn mode(&self) -> DlssPerfQualityMode {
+        MODES[self.current]
+    }
+
+    /// Change the target GPU frame time.
+    pub fn set_target_frame_time(&mut self, target_frame_time: Duration) {
+        self.target_frame_time = target_frame_time;
+    }
+}
+
+fn mode_index(mode: DlssPerfQualityMode) -> usize {
+    MODES
+        .iter()
+        .position(|preset| *preset == mode)
+        .unwrap_or_else(|| panic!("{mode:?} is not a preset DlssPerfQualityMode"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET: Duration = Duration::from_millis(10);
+
+    fn controller(hysteresis_frames: u32) -> DlssQualityController {
+        DlssQualityController::new(
+            TARGET,
+            DlssPerfQualityMode::Quality,
+            DlssPerfQualityMode::Performance,
+            hysteresis_frames,
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_hysteresis_frames() {
+        controller(0);
+    }
+
+    #[test]
+    fn within_budget_keeps_mode() {
+        let mut controller = controller(1);
+        controller.update(TARGET * 2);
+        assert_eq!(controller.mode(), DlssPerfQualityMode::Balanced);
+
+        // Under budget, but without enough headroom to step up in quality
+        for _ in 0..10 {
+            assert_eq!(controller.update(TARGET.mul_f32(0.9)), None);
+            assert_eq!(controller.update(TARGET), None);
+        }
+        assert_eq!(controller.mode(), DlssPerfQualityMode::Balanced);
+    }
+
+    #[test]
+    fn hysteresis() {
+        let mut controller = controller(3);
+        assert_eq!(controller.update(TARGET * 2), None);
+        assert_eq!(controller.update(TARGET * 2), None);
+        assert_eq!(
+            controller.update(TARGET * 2),
+            Some(DlssPerfQualityMode::Balanced)
+        );
+
+        assert_eq!(controller.update(TARGET / 2), None);
+        assert_eq!(controller.update(TARGET / 2), None);
+        assert_eq!(controller.update(TARGET), None);
+        assert_eq!(controller.update(TARGET / 2), None);
+        assert_eq!(controller.update(TARGET / 2), None);
+        assert_eq!(
+            controller.update(TARGET / 2),
+            Some(DlssPerfQualityMode::Quality)
+        );
+    }
+
+    #[test]
+    fn memory_pressure_steps_down() {
+        let mut controller = controller(3);
+        assert_eq!(
+            controller.update_memory_usage(95, 90),
+            Some(DlssPerfQualityMode::Balanced)
+        );
+        assert_eq!(controller.update_memory_usage(95, 90), None);
+        assert_eq!(controller.update_memory_usage(95, 90), None);
+        assert_eq!(
+            controller.update_memory_usage(95, 90),
+            Some(DlssPerfQualityMode::Performance)
+        );
+
+        // Already at the highest performance mode
+        for _ in 0..6 {
+            assert_eq!(controller.update_memory_usage(95, 90), None);
+        }
+        assert_eq!(controller.mode(), DlssPerfQualityMode::Performance);
+    }
+
+    #[test]
+    fn memory_pressure_blocks_step_up() {
+        let mut controller = controller(3);
+        controller.update_memory_usage(95, 90);
+        for _ in 0..10 {
+            controller.update_memory_usage(95, 90);
+            controller.update(TARGET / 2);
+        }
+        assert_eq!(controller.mode(), DlssPerfQualityMode::Performance);
+
+        // Frames under budget only count once usage is below the threshold
+        assert_eq!(controller.update_memory_usage(50, 90), None);
+        assert_eq!(controller.update(TARGET / 2), None);
+        assert_eq!(controller.update_memory_usage(50, 90), None);
+        assert_eq!(controller.update(TARGET / 2), None);
+        assert_eq!(controller.update_memory_usage(50, 90), None);
+        assert_eq!(
+            controller.update(TARGET / 2),
+            Some(DlssPerfQualityMode::Balanced)
+        );
+    }
+}
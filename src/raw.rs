@@ -0,0 +1,59 @@
+use crate::nvsdk_ngx::*;
+use ash::vk::{Format, Image, ImageSubresourceRange, ImageView};
+
+pub use crate::nvsdk_ngx::{NVSDK_NGX_Handle, NVSDK_NGX_Parameter};
+
+/// A Vulkan image view, for use with the raw Vulkan APIs such as
+/// [`crate::super_resolution::DlssSuperResolution::render_raw`].
+#[derive(Clone, Copy, Debug)]
+pub struct VulkanTexture {
+    /// The image view DLSS will access.
+    pub image_view: ImageView,
+    /// The image that [`Self::image_view`] was created from.
+    pub image: Image,
+    /// The subresource range of [`Self::image`] covered by [`Self::image_view`].
+    pub subresource_range: ImageSubresourceRange,
+    /// The format of [`Self::image_view`].
+    pub format: Format,
+    /// Width of the image, in pixels.
+    pub width: u32,
+    /// Height of the image, in pixels.
+    pub height: u32,
+    /// Whether the image was created with `VK_IMAGE_USAGE_STORAGE_BIT`.
+    ///
+    /// Must be true for output textures.
+    pub storage: bool,
+}
+
+impl VulkanTexture {
+    pub(crate) fn is_srgb(&self) -> bool {
+        matches!(
+            self.format,
+            Format::R8_SRGB
+                | Format::R8G8_SRGB
+                | Format::R8G8B8_SRGB
+                | Format::B8G8R8_SRGB
+                | Format::R8G8B8A8_SRGB
+                | Format::B8G8R8A8_SRGB
+                | Format::A8B8G8R8_SRGB_PACK32
+        )
+    }
+
+    pub(crate) fn size(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+
+    pub(crate) fn as_ngx(&self) -> NVSDK_NGX_Resource_VK {
+        unsafe {
+            NVSDK_NGX_Create_ImageView_Resource_VK(
+                self.image_view,
+                self.image,
+                self.subresource_range,
+                self.format,
+                self.width,
+                self.height,
+                self.storage,
+            )
+        }
+    }
+}
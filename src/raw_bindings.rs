@@ -0,0 +1 @@
+pub use crate::nvsdk_ngx::*;
@@ -1,20 +1,49 @@
-use crate::{DlssSdk, nvsdk_ngx::*};
+use crate::{
+    DlssArrayLayers, DlssCounters, DlssCreationTiming, DlssGpuTimeStatistics, DlssParseError,
+    DlssPassDescriptor, DlssPassRole, DlssPerfQualityMode, DlssRayReconstructionPreset, DlssSdk,
+    counters::CreationTimer,
+    gpu_timer::GpuTimer,
+    jitter::{
+        RAY_RECONSTRUCTION_MIN_JITTER_PHASE_COUNT, jitter_offset, jitter_phase_count, mip_bias,
+    },
+    nvsdk_ngx::*,
+    perf_quality_mode::normalize,
+    raw::VulkanTexture,
+    stable_hash::{perf_quality_mode_key, stable_hash},
+};
 use std::{
+    fmt::{self, Display, Formatter},
     iter, ptr,
-    sync::{Arc, Mutex},
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::Instant,
 };
 use wgpu::{
     Adapter, CommandBuffer, CommandEncoder, CommandEncoderDescriptor, Device, Queue, Texture,
-    TextureTransition, TextureUses, TextureView, hal::api::Vulkan,
+    TextureTransition, TextureView,
+    hal::{CommandEncoder as _, api::Vulkan},
 };
 
 /// Camera-specific object for using DLSS Ray Reconstruction.
+///
+/// Holds a strong reference to the [`Device`] and the [`DlssSdk`], keeping both alive until this context is dropped.
 pub struct DlssRayReconstruction {
     upscaled_resolution: [u32; 2],
     render_resolution: [u32; 2],
+    feature_flags: DlssFeatureFlags,
     device: Device,
     sdk: Arc<Mutex<DlssSdk>>,
+    sdk_generation: u64,
     feature: *mut NVSDK_NGX_Handle,
+    /// Boxed to keep the context cheap to move.
+    eval_params: Box<NVSDK_NGX_VK_DLSSD_Eval_Params>,
+    pub(crate) counters: DlssCounters,
+    creation_timer: CreationTimer,
+    gpu_timer: Option<GpuTimer>,
+    pending_reset: bool,
+    jitter_phase_offset: u32,
+    preset: DlssRayReconstructionPreset,
 }
 
 impl DlssRayReconstruction {
@@ -34,7 +63,46 @@ impl DlssRayReconstruction {
         device: &Device,
         queue: &Queue,
     ) -> Result<Self, DlssError> {
-        let locked_sdk = sdk.lock().unwrap();
+        let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("dlss_ray_reconstruction_context_creation"),
+        });
+
+        let dlss = unsafe {
+            command_encoder.as_hal_mut::<Vulkan, _, _>(|command_encoder| {
+                Self::new_raw(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    roughness_mode,
+                    depth_mode,
+                    sdk,
+                    command_encoder.unwrap().raw_handle(),
+                )
+            })?
+        };
+
+        queue.submit([command_encoder.finish()]);
+        dlss.creation_timer.time_gpu(queue);
+
+        Ok(dlss)
+    }
+
+    /// Like [`Self::new`], but records context creation commands into a raw Vulkan command buffer.
+    ///
+    /// # Safety
+    /// `command_buffer` must be in the recording state, and must have been allocated from the same `VkDevice` as `sdk`.
+    /// It must be submitted before calling [`Self::render`] or [`Self::render_raw`].
+    pub unsafe fn new_raw(
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        roughness_mode: DlssRayReconstructionRoughnessMode,
+        depth_mode: DlssRayReconstructionDepthMode,
+        sdk: Arc<Mutex<DlssSdk>>,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<Self, DlssError> {
+        let locked_sdk = sdk.lock().map_err(|_| DlssError::SdkPoisoned)?;
+        let creation_start = Instant::now();
 
         let perf_quality_value = perf_quality_mode.as_perf_quality_value(upscaled_resolution);
 
@@ -57,8 +125,17 @@ impl DlssRayReconstruction {
                 &mut deprecated_sharpness,
             ))?;
         }
-        if perf_quality_mode == DlssPerfQualityMode::Dlaa {
+        if perf_quality_mode.resolve(upscaled_resolution) == DlssPerfQualityMode::Dlaa {
             optimal_render_resolution = upscaled_resolution;
+            min_render_resolution = upscaled_resolution;
+            max_render_resolution = upscaled_resolution;
+        }
+        if let Some(custom_render_resolution) = perf_quality_mode.custom_render_resolution(
+            upscaled_resolution,
+            min_render_resolution,
+            max_render_resolution,
+        ) {
+            optimal_render_resolution = custom_render_resolution;
         }
 
         let mut create_params = NVSDK_NGX_DLSSD_Create_Params {
@@ -88,34 +165,40 @@ impl DlssRayReconstruction {
             InEnableOutputSubrects: feature_flags.contains(DlssFeatureFlags::OutputSubrect),
         };
 
-        let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
-            label: Some("dlss_ray_reconstruction_context_creation"),
-        });
-
         let mut feature = ptr::null_mut();
         unsafe {
-            let hal_device = device.as_hal::<Vulkan>().unwrap();
-            command_encoder.as_hal_mut::<Vulkan, _, _>(|command_encoder| {
-                check_ngx_result(NGX_VULKAN_CREATE_DLSSD_EXT1(
-                    hal_device.raw_device().handle(),
-                    command_encoder.unwrap().raw_handle(),
-                    1,
-                    1,
-                    &mut feature,
-                    locked_sdk.parameters,
-                    &mut create_params,
-                ))
-            })?
+            locked_sdk
+                .ray_reconstruction_presets
+                .apply(locked_sdk.parameters);
+            let hal_device = locked_sdk.device.as_hal::<Vulkan>().unwrap();
+            check_ngx_result(NGX_VULKAN_CREATE_DLSSD_EXT1(
+                hal_device.raw_device().handle(),
+                command_buffer,
+                1,
+                1,
+                &mut feature,
+                locked_sdk.parameters,
+                &mut create_params,
+            ))?;
         }
 
-        queue.submit([command_encoder.finish()]);
-
         Ok(Self {
             upscaled_resolution,
             render_resolution: optimal_render_resolution,
-            device: device.clone(),
+            feature_flags,
+            device: locked_sdk.device.clone(),
             sdk: Arc::clone(&sdk),
+            sdk_generation: locked_sdk.generation,
             feature,
+            eval_params: Box::new(eval_params_template()),
+            counters: DlssCounters::default(),
+            creation_timer: CreationTimer::new(creation_start.elapsed()),
+            gpu_timer: None,
+            pending_reset: true,
+            jitter_phase_offset: 0,
+            preset: locked_sdk
+                .ray_reconstruction_presets
+                .get(perf_quality_mode, upscaled_resolution),
         })
     }
 
@@ -135,168 +218,56 @@ impl DlssRayReconstruction {
         command_encoder: &mut CommandEncoder,
         adapter: &Adapter,
     ) -> Result<CommandBuffer, DlssError> {
-        render_parameters.validate()?;
-
-        let sdk = self.sdk.lock().unwrap();
+        self.render_array_layers(
+            render_parameters,
+            |_| DlssArrayLayers::default(),
+            command_encoder,
+            adapter,
+        )
+    }
 
-        let partial_texture_size = render_parameters
-            .partial_texture_size
-            .unwrap_or(self.render_resolution);
+    /// Like [`Self::render`], but for texture views of specific array layers, such as one eye of an OpenXR array swapchain.
+    ///
+    /// `array_layers` returns the layers covered by the texture view passed for each input and the output.
+    pub fn render_array_layers(
+        &mut self,
+        mut render_parameters: DlssRayReconstructionRenderParameters,
+        array_layers: impl Fn(DlssPassRole) -> DlssArrayLayers,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        if let Err(err) = check_adapter(&self.device, adapter).and_then(|()| {
+            render_parameters.validate(
+                self.feature_flags,
+                self.upscaled_resolution,
+                |texture_view| texture_view.texture().format().is_srgb(),
+                |texture_view| {
+                    let size = texture_view.texture().size();
+                    [size.width, size.height]
+                },
+            )
+        }) {
+            self.counters.validation_failures += 1;
+            return Err(err);
+        }
+        render_parameters.reset |= self.pending_reset;
 
-        // TODO: We may want to expose some more of these
-        let mut eval_params = NVSDK_NGX_VK_DLSSD_Eval_Params {
-            pInDiffuseAlbedo: &mut texture_to_ngx(render_parameters.diffuse_albedo, adapter)
-                as *mut _,
-            pInSpecularAlbedo: &mut texture_to_ngx(render_parameters.specular_albedo, adapter)
-                as *mut _,
-            pInNormals: &mut texture_to_ngx(render_parameters.normals, adapter) as *mut _,
-            pInRoughness: match render_parameters.roughness {
-                Some(roughness) => &mut texture_to_ngx(roughness, adapter) as *mut _,
-                None => ptr::null_mut(),
-            },
-            pInColor: &mut texture_to_ngx(render_parameters.color, adapter) as *mut _,
-            pInAlpha: ptr::null_mut(),
-            pInOutput: &mut texture_to_ngx(render_parameters.dlss_output, adapter) as *mut _,
-            pInOutputAlpha: ptr::null_mut(),
-            pInDepth: &mut texture_to_ngx(render_parameters.depth, adapter) as *mut _,
-            pInMotionVectors: &mut texture_to_ngx(render_parameters.motion_vectors, adapter)
-                as *mut _,
-            InJitterOffsetX: render_parameters.jitter_offset[0],
-            InJitterOffsetY: render_parameters.jitter_offset[1],
-            InRenderSubrectDimensions: NVSDK_NGX_Dimensions {
-                Width: partial_texture_size[0],
-                Height: partial_texture_size[1],
-            },
-            InReset: render_parameters.reset as _,
-            InMVScaleX: render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[0],
-            InMVScaleY: render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[1],
-            pInTransparencyMask: ptr::null_mut(),
-            pInExposureTexture: ptr::null_mut(),
-            pInBiasCurrentColorMask: match &render_parameters.bias {
-                Some(bias) => &mut texture_to_ngx(bias, adapter) as *mut _,
-                None => ptr::null_mut(),
-            },
-            InAlphaSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InOutputAlphaSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InDiffuseAlbedoSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InSpecularAlbedoSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InNormalsSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InRoughnessSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InDepthSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InMVSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InTranslucencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InBiasCurrentColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InOutputSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InPreExposure: 0.0,
-            InExposureScale: 0.0,
-            InIndicatorInvertXAxis: 0,
-            InIndicatorInvertYAxis: 0,
-            pInReflectedAlbedo: ptr::null_mut(),
-            pInColorBeforeParticles: ptr::null_mut(),
-            pInColorAfterParticles: ptr::null_mut(),
-            pInColorBeforeTransparency: ptr::null_mut(),
-            pInColorAfterTransparency: ptr::null_mut(),
-            pInColorBeforeFog: ptr::null_mut(),
-            pInColorAfterFog: ptr::null_mut(),
-            pInScreenSpaceSubsurfaceScatteringGuide: match &render_parameters
-                .screen_space_subsurface_scattering_guide
-            {
-                Some(screen_space_subsurface_scattering_guide) => {
-                    &mut texture_to_ngx(screen_space_subsurface_scattering_guide, adapter) as *mut _
-                }
-                None => ptr::null_mut(),
-            },
-            pInColorBeforeScreenSpaceSubsurfaceScattering: ptr::null_mut(),
-            pInColorAfterScreenSpaceSubsurfaceScattering: ptr::null_mut(),
-            pInScreenSpaceRefractionGuide: ptr::null_mut(),
-            pInColorBeforeScreenSpaceRefraction: ptr::null_mut(),
-            pInColorAfterScreenSpaceRefraction: ptr::null_mut(),
-            pInDepthOfFieldGuide: ptr::null_mut(),
-            pInColorBeforeDepthOfField: ptr::null_mut(),
-            pInColorAfterDepthOfField: ptr::null_mut(),
-            pInDiffuseHitDistance: ptr::null_mut(),
-            pInSpecularHitDistance: match render_parameters.specular_guide {
-                DlssRayReconstructionSpecularGuide::SpecularMotionVectors(_) => ptr::null_mut(),
-                DlssRayReconstructionSpecularGuide::SpecularHitDistance {
-                    texture_view, ..
-                } => &mut texture_to_ngx(texture_view, adapter) as *mut _,
-            },
-            pInDiffuseRayDirection: ptr::null_mut(),
-            pInSpecularRayDirection: ptr::null_mut(),
-            pInDiffuseRayDirectionHitDistance: ptr::null_mut(),
-            pInSpecularRayDirectionHitDistance: ptr::null_mut(),
-            InReflectedAlbedoSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorBeforeParticlesSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorAfterParticlesSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorBeforeTransparencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorAfterTransparencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorBeforeFogSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorAfterFogSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InScreenSpaceSubsurfaceScatteringGuideSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorBeforeScreenSpaceSubsurfaceScatteringSubrectBase: NVSDK_NGX_Coordinates {
-                X: 0,
-                Y: 0,
-            },
-            InColorAfterScreenSpaceSubsurfaceScatteringSubrectBase: NVSDK_NGX_Coordinates {
-                X: 0,
-                Y: 0,
-            },
-            InScreenSpaceRefractionGuideSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorBeforeScreenSpaceRefractionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorAfterScreenSpaceRefractionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InDepthOfFieldGuideSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorBeforeDepthOfFieldSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InColorAfterDepthOfFieldSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InDiffuseHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InSpecularHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InDiffuseRayDirectionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InSpecularRayDirectionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InDiffuseRayDirectionHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InSpecularRayDirectionHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            pInWorldToViewMatrix: match render_parameters.specular_guide {
-                DlssRayReconstructionSpecularGuide::SpecularMotionVectors(_) => ptr::null_mut(),
-                DlssRayReconstructionSpecularGuide::SpecularHitDistance {
-                    mut world_to_view_rows_array,
-                    ..
-                } => &mut world_to_view_rows_array as *mut _,
-            },
-            pInViewToClipMatrix: match render_parameters.specular_guide {
-                DlssRayReconstructionSpecularGuide::SpecularMotionVectors(_) => ptr::null_mut(),
-                DlssRayReconstructionSpecularGuide::SpecularHitDistance {
-                    mut view_to_clip_rows_array,
-                    ..
-                } => &mut view_to_clip_rows_array as *mut _,
-            },
-            GBufferSurface: NVSDK_NGX_VK_GBuffer {
-                pInAttrib: [ptr::null_mut(); 16],
-            },
-            InToneMapperType: NVSDK_NGX_ToneMapperType_NVSDK_NGX_TONEMAPPER_STRING,
-            pInMotionVectors3D: ptr::null_mut(),
-            pInIsParticleMask: ptr::null_mut(),
-            pInAnimatedTextureMask: ptr::null_mut(),
-            pInDepthHighRes: ptr::null_mut(),
-            pInPositionViewSpace: ptr::null_mut(),
-            InFrameTimeDeltaInMsec: 0.0,
-            pInRayTracingHitDistance: ptr::null_mut(),
-            pInMotionVectorsReflections: match render_parameters.specular_guide {
-                DlssRayReconstructionSpecularGuide::SpecularMotionVectors(
-                    specular_motion_vectors,
-                ) => &mut texture_to_ngx(specular_motion_vectors, adapter) as *mut _,
-                DlssRayReconstructionSpecularGuide::SpecularHitDistance { .. } => ptr::null_mut(),
-            },
-            pInTransparencyLayer: ptr::null_mut(),
-            InTransparencyLayerSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            pInTransparencyLayerOpacity: ptr::null_mut(),
-            InTransparencyLayerOpacitySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            pInTransparencyLayerMvecs: ptr::null_mut(),
-            InTransparencyLayerMvecsSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            pInDisocclusionMask: ptr::null_mut(),
-            InDisocclusionMaskSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-        };
+        let mut resources = render_parameters.ngx_resources(|role, texture_view| {
+            texture_to_ngx(texture_view, array_layers(role), adapter)
+        });
 
         command_encoder.transition_resources(iter::empty(), render_parameters.barrier_list());
 
+        // DLSS commands are recorded via wgpu-hal, and so do not show up in wgpu API traces
+        command_encoder.insert_debug_marker(&format!(
+            "dlss_ray_reconstruction upscaled_resolution={:?} render_resolution={:?} reset={}",
+            self.upscaled_resolution,
+            render_parameters
+                .partial_texture_size
+                .unwrap_or(self.render_resolution),
+            render_parameters.reset,
+        ));
+
         let mut dlss_command_encoder =
             self.device
                 .create_command_encoder(&CommandEncoderDescriptor {
@@ -304,29 +275,237 @@ impl DlssRayReconstruction {
                 });
         unsafe {
             dlss_command_encoder.as_hal_mut::<Vulkan, _, _>(|command_encoder| {
-                check_ngx_result(NGX_VULKAN_EVALUATE_DLSSD_EXT(
-                    command_encoder.unwrap().raw_handle(),
-                    self.feature,
-                    sdk.parameters,
-                    &mut eval_params,
-                ))
+                let command_encoder = command_encoder.unwrap();
+                command_encoder.begin_debug_marker("dlss_ray_reconstruction");
+                let result = self.evaluate_timed(
+                    &render_parameters,
+                    &mut resources,
+                    command_encoder.raw_handle(),
+                );
+                command_encoder.end_debug_marker();
+                result
             })?;
         }
+        self.counters.record_evaluation(render_parameters.reset);
+        self.pending_reset = false;
+
         Ok(dlss_command_encoder.finish())
     }
 
+    /// Like [`Self::render`], but uses raw Vulkan textures and records into a raw Vulkan command buffer.
+    ///
+    /// # Safety
+    /// `command_buffer` must be in the recording state, and must have been allocated from the same `VkDevice` as the [`DlssSdk`].
+    ///
+    /// `command_buffer` may be a secondary command buffer, as long as it was not begun with `VK_COMMAND_BUFFER_USAGE_RENDER_PASS_CONTINUE_BIT`.
+    /// It must then be executed from a primary command buffer outside of a render pass.
+    ///
+    /// Input textures must be in `VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL`, and the output texture in `VK_IMAGE_LAYOUT_GENERAL`.
+    ///
+    /// DLSS will modify the command buffer's bound pipeline, descriptor sets, and push constants.
+    pub unsafe fn render_raw(
+        &mut self,
+        mut render_parameters: DlssRayReconstructionRenderParameters<VulkanTexture>,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<(), DlssError> {
+        if let Err(err) = render_parameters.validate(
+            self.feature_flags,
+            self.upscaled_resolution,
+            VulkanTexture::is_srgb,
+            VulkanTexture::size,
+        ) {
+            self.counters.validation_failures += 1;
+            return Err(err);
+        }
+        render_parameters.reset |= self.pending_reset;
+
+        let mut resources = render_parameters.ngx_resources(|_, texture| texture.as_ngx());
+
+        unsafe { self.evaluate_timed(&render_parameters, &mut resources, command_buffer)? };
+        self.counters.record_evaluation(render_parameters.reset);
+        self.pending_reset = false;
+
+        Ok(())
+    }
+
+    unsafe fn evaluate_timed<T>(
+        &mut self,
+        render_parameters: &DlssRayReconstructionRenderParameters<T>,
+        resources: &mut DlssRayReconstructionResources,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<(), DlssError> {
+        let mut gpu_timer = self.gpu_timer.take();
+        let result = unsafe {
+            match &mut gpu_timer {
+                Some(gpu_timer) => gpu_timer.time(command_buffer, || {
+                    self.evaluate(render_parameters, resources, command_buffer)
+                }),
+                None => self.evaluate(render_parameters, resources, command_buffer),
+            }
+        };
+        self.gpu_timer = gpu_timer;
+        result
+    }
+
+    unsafe fn evaluate<T>(
+        &mut self,
+        render_parameters: &DlssRayReconstructionRenderParameters<T>,
+        resources: &mut DlssRayReconstructionResources,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<(), DlssError> {
+        let sdk = self.sdk.lock().map_err(|_| DlssError::SdkPoisoned)?;
+        if sdk.generation != self.sdk_generation {
+            return Err(DlssError::SdkReinitialized);
+        }
+
+        let partial_texture_size = render_parameters
+            .partial_texture_size
+            .unwrap_or(self.render_resolution);
+        let output_subrect_base = render_parameters.output_subrect_base.unwrap_or([0, 0]);
+
+        // Every resource pointer is overwritten each frame, so pointers to a previous frame's resources are never read
+        let eval_params = &mut *self.eval_params;
+        eval_params.pInDiffuseAlbedo = &mut resources.diffuse_albedo;
+        eval_params.pInSpecularAlbedo = &mut resources.specular_albedo;
+        eval_params.pInNormals = &mut resources.normals;
+        eval_params.pInRoughness = optional_resource(&mut resources.roughness);
+        eval_params.pInColor = &mut resources.color;
+        eval_params.pInOutput = &mut resources.dlss_output;
+        eval_params.pInDepth = &mut resources.depth;
+        eval_params.pInMotionVectors = &mut resources.motion_vectors;
+        eval_params.InJitterOffsetX = render_parameters.jitter_offset[0];
+        eval_params.InJitterOffsetY = render_parameters.jitter_offset[1];
+        eval_params.InRenderSubrectDimensions = NVSDK_NGX_Dimensions {
+            Width: partial_texture_size[0],
+            Height: partial_texture_size[1],
+        };
+        eval_params.InReset = render_parameters.reset as _;
+        eval_params.InMVScaleX = render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[0];
+        eval_params.InMVScaleY = render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[1];
+        eval_params.pInBiasCurrentColorMask = optional_resource(&mut resources.bias);
+        eval_params.InOutputSubrectBase = NVSDK_NGX_Coordinates {
+            X: output_subrect_base[0],
+            Y: output_subrect_base[1],
+        };
+        eval_params.pInScreenSpaceSubsurfaceScatteringGuide =
+            optional_resource(&mut resources.screen_space_subsurface_scattering_guide);
+        eval_params.pInSpecularHitDistance =
+            optional_resource(&mut resources.specular_hit_distance);
+        eval_params.pInWorldToViewMatrix = match &mut resources.world_to_view_rows_array {
+            Some(world_to_view_rows_array) => world_to_view_rows_array.as_mut_ptr(),
+            None => ptr::null_mut(),
+        };
+        eval_params.pInViewToClipMatrix = match &mut resources.view_to_clip_rows_array {
+            Some(view_to_clip_rows_array) => view_to_clip_rows_array.as_mut_ptr(),
+            None => ptr::null_mut(),
+        };
+        eval_params.pInMotionVectorsReflections =
+            optional_resource(&mut resources.specular_motion_vectors);
+
+        unsafe {
+            check_ngx_result(NGX_VULKAN_EVALUATE_DLSSD_EXT(
+                command_buffer,
+                self.feature,
+                sdk.parameters,
+                eval_params,
+            ))
+        }
+    }
+
     /// Suggested subpixel camera jitter for a given frame.
     pub fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
-        let ratio = self.upscaled_resolution[0] as f32 / render_resolution[0] as f32;
-        let phase_count = ((8.0 * ratio * ratio) as u32).max(32);
-        let i = frame_number % phase_count;
+        jitter_offset(
+            frame_number.wrapping_add(self.jitter_phase_offset),
+            self.suggested_jitter_phase_count(render_resolution),
+        )
+    }
+
+    /// Offset the sequence returned by [`Self::suggested_jitter`] by `offset` phases.
+    ///
+    /// Give simultaneous cameras, such as stereo eyes, different offsets so that they do not sample identical jitter each frame.
+    /// The offset must be set again after recreating the context.
+    pub fn set_jitter_phase_offset(&mut self, offset: u32) {
+        self.jitter_phase_offset = offset;
+    }
 
-        [halton_sequence(i, 2) - 0.5, halton_sequence(i, 3) - 0.5]
+    /// The offset set by [`Self::set_jitter_phase_offset`], 0 by default.
+    pub fn jitter_phase_offset(&self) -> u32 {
+        self.jitter_phase_offset
+    }
+
+    /// Suggested number of jitter phases to cycle through before repeating, used by [`Self::suggested_jitter`].
+    pub fn suggested_jitter_phase_count(&self, render_resolution: [u32; 2]) -> u32 {
+        jitter_phase_count(render_resolution, self.upscaled_resolution)
+            .max(RAY_RECONSTRUCTION_MIN_JITTER_PHASE_COUNT)
     }
 
     /// Suggested mip bias to apply when sampling textures.
     pub fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
-        (render_resolution[0] as f32 / self.upscaled_resolution[0] as f32).log2() - 1.0
+        mip_bias(render_resolution, self.upscaled_resolution)
+    }
+
+    /// Start measuring the GPU time of each evaluation, for [`Self::gpu_time_statistics`].
+    ///
+    /// Timing is measured using Vulkan timestamp queries, and requires `queue` to support them.
+    /// An evaluation's timing is available once the next evaluation has been rendered and `queue` has finished the former.
+    pub fn enable_gpu_timing(&mut self, queue: &Queue) -> Result<(), DlssError> {
+        if self.gpu_timer.is_none() {
+            self.gpu_timer =
+                Some(GpuTimer::new(&self.device, queue).map_err(|_| DlssError::PlatformError)?);
+        }
+        Ok(())
+    }
+
+    /// Rolling statistics of the GPU time taken by recent evaluations.
+    ///
+    /// Returns `None` if [`Self::enable_gpu_timing`] has not been called, or no evaluations have completed on the GPU yet.
+    pub fn gpu_time_statistics(&mut self) -> Option<DlssGpuTimeStatistics> {
+        let gpu_timer = self.gpu_timer.as_mut()?;
+        gpu_timer.poll();
+        gpu_timer.statistics()
+    }
+
+    /// Whether the next evaluation will reset temporal history regardless of the `reset` render parameter.
+    ///
+    /// This is true for newly created contexts, including those recreated after a settings or resolution change,
+    /// and after calling [`Self::request_reset`].
+    pub fn pending_reset(&self) -> bool {
+        self.pending_reset
+    }
+
+    /// Reset temporal history on the next evaluation.
+    pub fn request_reset(&mut self) {
+        self.pending_reset = true;
+    }
+
+    /// The underlying NGX feature handle, for calling NGX functions this crate does not wrap.
+    ///
+    /// Use [`DlssSdk::raw_parameters`] for the NGX parameters this context was created with.
+    ///
+    /// # Safety
+    /// The handle must not be released, and must only be used while the [`DlssSdk`] mutex is locked.
+    pub unsafe fn raw_feature_handle(&self) -> *mut NVSDK_NGX_Handle {
+        self.feature
+    }
+
+    /// Usage counters for this context.
+    pub fn counters(&self) -> DlssCounters {
+        self.counters
+    }
+
+    /// How long creating this context took.
+    pub fn creation_timing(&self) -> DlssCreationTiming {
+        self.creation_timer.timing()
+    }
+
+    /// The [`Device`] this context was created on, which it keeps alive.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The [`DlssSdk`] this context was created from, which it keeps alive.
+    pub fn sdk(&self) -> &Arc<Mutex<DlssSdk>> {
+        &self.sdk
     }
 
     /// The upscaled resolution DLSS will output at.
@@ -338,19 +517,33 @@ impl DlssRayReconstruction {
     pub fn render_resolution(&self) -> [u32; 2] {
         self.render_resolution
     }
+
+    /// The preset this context was created with, see [`DlssSdk::set_ray_reconstruction_presets`].
+    pub fn preset(&self) -> DlssRayReconstructionPreset {
+        self.preset
+    }
 }
 
 impl Drop for DlssRayReconstruction {
     fn drop(&mut self) {
+        // Serialize with other NGX calls, even if the lock was poisoned by a panic
+        let sdk = self.sdk.lock().unwrap_or_else(PoisonError::into_inner);
+        // The feature was already released if the SDK was reinitialized
+        if sdk.generation != self.sdk_generation {
+            return;
+        }
+
         unsafe {
             let hal_device = self.device.as_hal::<Vulkan>().unwrap();
-            hal_device
-                .raw_device()
-                .device_wait_idle()
-                .expect("Failed to wait for idle device when destroying DlssRayReconstruction");
+            let wait_result = hal_device.raw_device().device_wait_idle();
+            let release_result = check_ngx_result(NVSDK_NGX_VULKAN_ReleaseFeature(self.feature));
 
-            check_ngx_result(NVSDK_NGX_VULKAN_ReleaseFeature(self.feature))
-                .expect("Failed to destroy DlssRayReconstruction feature");
+            // Panicking while already unwinding would abort
+            if !thread::panicking() {
+                wait_result
+                    .expect("Failed to wait for idle device when destroying DlssRayReconstruction");
+                release_result.expect("Failed to destroy DlssRayReconstruction feature");
+            }
         }
     }
 }
@@ -358,7 +551,125 @@ impl Drop for DlssRayReconstruction {
 unsafe impl Send for DlssRayReconstruction {}
 unsafe impl Sync for DlssRayReconstruction {}
 
+/// Eval params for a new context. The context keeps them for its lifetime, and only patches the per-frame fields when rendering.
+fn eval_params_template() -> NVSDK_NGX_VK_DLSSD_Eval_Params {
+    // TODO: We may want to expose some more of these
+    NVSDK_NGX_VK_DLSSD_Eval_Params {
+        pInDiffuseAlbedo: ptr::null_mut(),
+        pInSpecularAlbedo: ptr::null_mut(),
+        pInNormals: ptr::null_mut(),
+        pInRoughness: ptr::null_mut(),
+        pInColor: ptr::null_mut(),
+        pInAlpha: ptr::null_mut(),
+        pInOutput: ptr::null_mut(),
+        pInOutputAlpha: ptr::null_mut(),
+        pInDepth: ptr::null_mut(),
+        pInMotionVectors: ptr::null_mut(),
+        InJitterOffsetX: 0.0,
+        InJitterOffsetY: 0.0,
+        InRenderSubrectDimensions: NVSDK_NGX_Dimensions {
+            Width: 0,
+            Height: 0,
+        },
+        InReset: 0,
+        InMVScaleX: 1.0,
+        InMVScaleY: 1.0,
+        pInTransparencyMask: ptr::null_mut(),
+        pInExposureTexture: ptr::null_mut(),
+        pInBiasCurrentColorMask: ptr::null_mut(),
+        InAlphaSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InOutputAlphaSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InDiffuseAlbedoSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InSpecularAlbedoSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InNormalsSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InRoughnessSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InDepthSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InMVSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InTranslucencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InBiasCurrentColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InOutputSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InPreExposure: 0.0,
+        InExposureScale: 0.0,
+        InIndicatorInvertXAxis: 0,
+        InIndicatorInvertYAxis: 0,
+        pInReflectedAlbedo: ptr::null_mut(),
+        pInColorBeforeParticles: ptr::null_mut(),
+        pInColorAfterParticles: ptr::null_mut(),
+        pInColorBeforeTransparency: ptr::null_mut(),
+        pInColorAfterTransparency: ptr::null_mut(),
+        pInColorBeforeFog: ptr::null_mut(),
+        pInColorAfterFog: ptr::null_mut(),
+        pInScreenSpaceSubsurfaceScatteringGuide: ptr::null_mut(),
+        pInColorBeforeScreenSpaceSubsurfaceScattering: ptr::null_mut(),
+        pInColorAfterScreenSpaceSubsurfaceScattering: ptr::null_mut(),
+        pInScreenSpaceRefractionGuide: ptr::null_mut(),
+        pInColorBeforeScreenSpaceRefraction: ptr::null_mut(),
+        pInColorAfterScreenSpaceRefraction: ptr::null_mut(),
+        pInDepthOfFieldGuide: ptr::null_mut(),
+        pInColorBeforeDepthOfField: ptr::null_mut(),
+        pInColorAfterDepthOfField: ptr::null_mut(),
+        pInDiffuseHitDistance: ptr::null_mut(),
+        pInSpecularHitDistance: ptr::null_mut(),
+        pInDiffuseRayDirection: ptr::null_mut(),
+        pInSpecularRayDirection: ptr::null_mut(),
+        pInDiffuseRayDirectionHitDistance: ptr::null_mut(),
+        pInSpecularRayDirectionHitDistance: ptr::null_mut(),
+        InReflectedAlbedoSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorBeforeParticlesSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorAfterParticlesSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorBeforeTransparencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorAfterTransparencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorBeforeFogSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorAfterFogSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InScreenSpaceSubsurfaceScatteringGuideSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorBeforeScreenSpaceSubsurfaceScatteringSubrectBase: NVSDK_NGX_Coordinates {
+            X: 0,
+            Y: 0,
+        },
+        InColorAfterScreenSpaceSubsurfaceScatteringSubrectBase: NVSDK_NGX_Coordinates {
+            X: 0,
+            Y: 0,
+        },
+        InScreenSpaceRefractionGuideSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorBeforeScreenSpaceRefractionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorAfterScreenSpaceRefractionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InDepthOfFieldGuideSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorBeforeDepthOfFieldSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InColorAfterDepthOfFieldSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InDiffuseHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InSpecularHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InDiffuseRayDirectionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InSpecularRayDirectionSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InDiffuseRayDirectionHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InSpecularRayDirectionHitDistanceSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        pInWorldToViewMatrix: ptr::null_mut(),
+        pInViewToClipMatrix: ptr::null_mut(),
+        GBufferSurface: NVSDK_NGX_VK_GBuffer {
+            pInAttrib: [ptr::null_mut(); 16],
+        },
+        InToneMapperType: NVSDK_NGX_ToneMapperType_NVSDK_NGX_TONEMAPPER_STRING,
+        pInMotionVectors3D: ptr::null_mut(),
+        pInIsParticleMask: ptr::null_mut(),
+        pInAnimatedTextureMask: ptr::null_mut(),
+        pInDepthHighRes: ptr::null_mut(),
+        pInPositionViewSpace: ptr::null_mut(),
+        InFrameTimeDeltaInMsec: 0.0,
+        pInRayTracingHitDistance: ptr::null_mut(),
+        pInMotionVectorsReflections: ptr::null_mut(),
+        pInTransparencyLayer: ptr::null_mut(),
+        InTransparencyLayerSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        pInTransparencyLayerOpacity: ptr::null_mut(),
+        InTransparencyLayerOpacitySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        pInTransparencyLayerMvecs: ptr::null_mut(),
+        InTransparencyLayerMvecsSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        pInDisocclusionMask: ptr::null_mut(),
+        InDisocclusionMaskSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+    }
+}
+
 /// How roughness will be provided to [`DlssRayReconstruction`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum DlssRayReconstructionRoughnessMode {
     /// Roughness is provided as a standalone texture in [`DlssRayReconstructionRenderParameters::roughness`].
     Unpacked,
@@ -367,6 +678,7 @@ pub enum DlssRayReconstructionRoughnessMode {
 }
 
 /// How depth will be provided to [`DlssRayReconstruction`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum DlssRayReconstructionDepthMode {
     /// Depth will be linear in view-space.
     Linear,
@@ -374,42 +686,112 @@ pub enum DlssRayReconstructionDepthMode {
     Hardware,
 }
 
+impl Display for DlssRayReconstructionRoughnessMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unpacked => "unpacked",
+            Self::Packed => "packed",
+        })
+    }
+}
+
+impl FromStr for DlssRayReconstructionRoughnessMode {
+    type Err = DlssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize(s).as_str() {
+            "unpacked" => Ok(Self::Unpacked),
+            "packed" => Ok(Self::Packed),
+            _ => Err(DlssParseError(s.to_owned())),
+        }
+    }
+}
+
+impl Display for DlssRayReconstructionDepthMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Linear => "linear",
+            Self::Hardware => "hardware",
+        })
+    }
+}
+
+impl FromStr for DlssRayReconstructionDepthMode {
+    type Err = DlssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize(s).as_str() {
+            "linear" => Ok(Self::Linear),
+            "hardware" => Ok(Self::Hardware),
+            _ => Err(DlssParseError(s.to_owned())),
+        }
+    }
+}
+
+/// Settings a [`DlssRayReconstruction`] context is created with, for use as a cache key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DlssRayReconstructionSettings {
+    pub upscaled_resolution: [u32; 2],
+    pub perf_quality_mode: DlssPerfQualityMode,
+    pub feature_flags: DlssFeatureFlags,
+    pub roughness_mode: DlssRayReconstructionRoughnessMode,
+    pub depth_mode: DlssRayReconstructionDepthMode,
+}
+
+impl DlssRayReconstructionSettings {
+    /// A hash of the settings that is stable across platforms and runs, unlike [`std::hash::DefaultHasher`].
+    pub fn settings_hash(&self) -> u64 {
+        stable_hash(&[
+            self.upscaled_resolution[0] as u64,
+            self.upscaled_resolution[1] as u64,
+            perf_quality_mode_key(self.perf_quality_mode),
+            self.feature_flags.bits() as u64,
+            self.roughness_mode as u64,
+            self.depth_mode as u64,
+        ])
+    }
+}
+
 /// Inputs and output resources needed for rendering [`DlssRayReconstruction`].
-pub struct DlssRayReconstructionRenderParameters<'a> {
+///
+/// Textures are [`TextureView`]s for [`DlssRayReconstruction::render`], and [`VulkanTexture`]s for [`DlssRayReconstruction::render_raw`].
+pub struct DlssRayReconstructionRenderParameters<'a, T = TextureView> {
     /// Diffuse albedo.
-    pub diffuse_albedo: &'a TextureView,
+    pub diffuse_albedo: &'a T,
     /// Specular albedo.
     ///
     /// See section 3.4.2 of `$DLSS_SDK/doc/DLSS-RR Integration Guide.pdf` for how to calculate this texture.
-    pub specular_albedo: &'a TextureView,
+    pub specular_albedo: &'a T,
     /// Normals.
     ///
     /// Can be view-space or world-space.
     ///
     /// Must have linear material roughness in the alpha channel when using [`DlssRayReconstructionRoughnessMode::Packed`].
-    pub normals: &'a TextureView,
+    pub normals: &'a T,
     /// Linear material roughness.
     ///
     /// Must be provided when using [`DlssRayReconstructionRoughnessMode::Unpacked`].
-    pub roughness: Option<&'a TextureView>,
+    pub roughness: Option<&'a T>,
     /// Main color view of your camera.
-    pub color: &'a TextureView,
+    pub color: &'a T,
     /// Depth buffer.
     ///
     /// See [`DlssRayReconstructionDepthMode`] for format.
-    pub depth: &'a TextureView,
+    pub depth: &'a T,
     /// Motion vectors.
-    pub motion_vectors: &'a TextureView,
+    ///
+    /// Must be at the render resolution if [`DlssFeatureFlags::LowResolutionMotionVectors`] is set, and otherwise at the upscaled resolution.
+    pub motion_vectors: &'a T,
     /// Specular material guide.
-    pub specular_guide: DlssRayReconstructionSpecularGuide<'a>,
+    pub specular_guide: DlssRayReconstructionSpecularGuide<'a, T>,
     /// Screen-space subsurface scattering guide.
     ///
     /// See section 3.4.12 of `$DLSS_SDK/doc/DLSS-RR Integration Guide.pdf` for how to calculate this texture
-    pub screen_space_subsurface_scattering_guide: Option<&'a TextureView>,
+    pub screen_space_subsurface_scattering_guide: Option<&'a T>,
     /// Optional per-pixel bias to make DLSS more reactive.
-    pub bias: Option<&'a TextureView>,
-    /// The texture DLSS outputs to.
-    pub dlss_output: &'a TextureView,
+    pub bias: Option<&'a T>,
+    /// The texture DLSS outputs to. Must not have an sRGB format.
+    pub dlss_output: &'a T,
     /// Whether DLSS should reset temporal history, useful for camera cuts.
     pub reset: bool,
     /// Subpixel jitter that was applied to your camera.
@@ -417,18 +799,22 @@ pub struct DlssRayReconstructionRenderParameters<'a> {
     /// Optionally use only a specific subrect of the input textures, rather than the whole textures.
     // TODO: Allow configuring partial texture origins
     pub partial_texture_size: Option<[u32; 2]>,
+    /// Optionally write to a subrect of [`Self::dlss_output`] starting at this origin, rather than writing to the whole texture.
+    ///
+    /// Requires [`DlssFeatureFlags::OutputSubrect`].
+    pub output_subrect_base: Option<[u32; 2]>,
     /// Optional scaling factor to apply to the values contained within [`Self::motion_vectors`].
     pub motion_vector_scale: Option<[f32; 2]>,
 }
 
 /// Guide buffer for specular material handling.
-pub enum DlssRayReconstructionSpecularGuide<'a> {
+pub enum DlssRayReconstructionSpecularGuide<'a, T = TextureView> {
     /// Motion vectors for objects reflected in specular material pixels.
-    SpecularMotionVectors(&'a TextureView),
+    SpecularMotionVectors(&'a T),
     /// World-space distance between primary vertex and hit point from tracing specular material pixels.
     SpecularHitDistance {
         /// Specular hit distance texture.
-        texture_view: &'a TextureView,
+        texture_view: &'a T,
         /// World-space to view-space camera matrix, as rows array.
         world_to_view_rows_array: [f32; 16],
         /// View-space to clip-space camera matrix, as rows array.
@@ -436,48 +822,138 @@ pub enum DlssRayReconstructionSpecularGuide<'a> {
     },
 }
 
-impl<'a> DlssRayReconstructionRenderParameters<'a> {
-    fn validate(&self) -> Result<(), DlssError> {
-        // TODO
-        Ok(())
-    }
-
-    fn barrier_list(&self) -> impl Iterator<Item = TextureTransition<&'a Texture>> {
-        fn resource_barrier(texture_view: &TextureView) -> TextureTransition<&Texture> {
-            TextureTransition {
-                texture: texture_view.texture(),
-                selector: None,
-                state: TextureUses::RESOURCE,
-            }
+impl<'a, T> DlssRayReconstructionRenderParameters<'a, T> {
+    fn validate(
+        &self,
+        feature_flags: DlssFeatureFlags,
+        upscaled_resolution: [u32; 2],
+        is_srgb: impl Fn(&T) -> bool,
+        size: impl Fn(&T) -> [u32; 2],
+    ) -> Result<(), DlssError> {
+        // TODO: Validate the remaining parameters
+        if is_srgb(self.dlss_output) {
+            return Err(DlssError::SrgbOutput);
         }
+        check_motion_vectors(
+            feature_flags,
+            upscaled_resolution,
+            size(self.color),
+            size(self.motion_vectors),
+            self.motion_vector_scale,
+        )
+    }
 
-        [
-            Some(resource_barrier(self.diffuse_albedo)),
-            Some(resource_barrier(self.specular_albedo)),
-            Some(resource_barrier(self.normals)),
-            self.roughness.map(resource_barrier),
-            Some(resource_barrier(self.color)),
-            Some(resource_barrier(self.depth)),
-            Some(resource_barrier(self.motion_vectors)),
+    fn ngx_resources(
+        &self,
+        to_ngx: impl Fn(DlssPassRole, &T) -> NVSDK_NGX_Resource_VK,
+    ) -> DlssRayReconstructionResources {
+        let (specular_motion_vectors, specular_hit_distance, world_to_view, view_to_clip) =
             match &self.specular_guide {
                 DlssRayReconstructionSpecularGuide::SpecularMotionVectors(
                     specular_motion_vectors,
-                ) => Some(resource_barrier(specular_motion_vectors)),
+                ) => (
+                    Some(to_ngx(
+                        DlssPassRole::SpecularMotionVectors,
+                        specular_motion_vectors,
+                    )),
+                    None,
+                    None,
+                    None,
+                ),
                 DlssRayReconstructionSpecularGuide::SpecularHitDistance {
-                    texture_view: specular_hit_distance,
-                    ..
-                } => Some(resource_barrier(specular_hit_distance)),
-            },
-            self.screen_space_subsurface_scattering_guide
-                .map(resource_barrier),
-            self.bias.map(resource_barrier),
-            Some(TextureTransition {
-                texture: self.dlss_output.texture(),
-                selector: None,
-                state: TextureUses::STORAGE_READ_WRITE,
-            }),
-        ]
-        .into_iter()
-        .flatten()
+                    texture_view,
+                    world_to_view_rows_array,
+                    view_to_clip_rows_array,
+                } => (
+                    None,
+                    Some(to_ngx(DlssPassRole::SpecularHitDistance, texture_view)),
+                    Some(*world_to_view_rows_array),
+                    Some(*view_to_clip_rows_array),
+                ),
+            };
+
+        DlssRayReconstructionResources {
+            diffuse_albedo: to_ngx(DlssPassRole::DiffuseAlbedo, self.diffuse_albedo),
+            specular_albedo: to_ngx(DlssPassRole::SpecularAlbedo, self.specular_albedo),
+            normals: to_ngx(DlssPassRole::Normals, self.normals),
+            roughness: self
+                .roughness
+                .map(|roughness| to_ngx(DlssPassRole::Roughness, roughness)),
+            color: to_ngx(DlssPassRole::Color, self.color),
+            depth: to_ngx(DlssPassRole::Depth, self.depth),
+            motion_vectors: to_ngx(DlssPassRole::MotionVectors, self.motion_vectors),
+            specular_motion_vectors,
+            specular_hit_distance,
+            world_to_view_rows_array: world_to_view,
+            view_to_clip_rows_array: view_to_clip,
+            screen_space_subsurface_scattering_guide: self
+                .screen_space_subsurface_scattering_guide
+                .map(|guide| to_ngx(DlssPassRole::ScreenSpaceSubsurfaceScatteringGuide, guide)),
+            bias: self.bias.map(|bias| to_ngx(DlssPassRole::Bias, bias)),
+            dlss_output: to_ngx(DlssPassRole::Output, self.dlss_output),
+        }
+    }
+}
+
+impl<'a> DlssRayReconstructionRenderParameters<'a> {
+    /// The textures this evaluation reads and writes, for declaring its dependencies in a render graph.
+    pub fn pass_descriptor(&self) -> DlssPassDescriptor<'a> {
+        DlssPassDescriptor::new(
+            [
+                Some((DlssPassRole::DiffuseAlbedo, self.diffuse_albedo.texture())),
+                Some((DlssPassRole::SpecularAlbedo, self.specular_albedo.texture())),
+                Some((DlssPassRole::Normals, self.normals.texture())),
+                self.roughness
+                    .map(|roughness| (DlssPassRole::Roughness, roughness.texture())),
+                Some((DlssPassRole::Color, self.color.texture())),
+                Some((DlssPassRole::Depth, self.depth.texture())),
+                Some((DlssPassRole::MotionVectors, self.motion_vectors.texture())),
+                match &self.specular_guide {
+                    DlssRayReconstructionSpecularGuide::SpecularMotionVectors(
+                        specular_motion_vectors,
+                    ) => Some((
+                        DlssPassRole::SpecularMotionVectors,
+                        specular_motion_vectors.texture(),
+                    )),
+                    DlssRayReconstructionSpecularGuide::SpecularHitDistance {
+                        texture_view: specular_hit_distance,
+                        ..
+                    } => Some((
+                        DlssPassRole::SpecularHitDistance,
+                        specular_hit_distance.texture(),
+                    )),
+                },
+                self.screen_space_subsurface_scattering_guide.map(|guide| {
+                    (
+                        DlssPassRole::ScreenSpaceSubsurfaceScatteringGuide,
+                        guide.texture(),
+                    )
+                }),
+                self.bias.map(|bias| (DlssPassRole::Bias, bias.texture())),
+            ],
+            self.dlss_output.texture(),
+        )
     }
+
+    fn barrier_list(&self) -> impl Iterator<Item = TextureTransition<&'a Texture>> {
+        self.pass_descriptor().transitions()
+    }
+}
+
+/// NGX resources referenced by [`NVSDK_NGX_VK_DLSSD_Eval_Params`], kept alive for the duration of evaluation.
+struct DlssRayReconstructionResources {
+    diffuse_albedo: NVSDK_NGX_Resource_VK,
+    specular_albedo: NVSDK_NGX_Resource_VK,
+    normals: NVSDK_NGX_Resource_VK,
+    roughness: Option<NVSDK_NGX_Resource_VK>,
+    color: NVSDK_NGX_Resource_VK,
+    depth: NVSDK_NGX_Resource_VK,
+    motion_vectors: NVSDK_NGX_Resource_VK,
+    specular_motion_vectors: Option<NVSDK_NGX_Resource_VK>,
+    specular_hit_distance: Option<NVSDK_NGX_Resource_VK>,
+    world_to_view_rows_array: Option<[f32; 16]>,
+    view_to_clip_rows_array: Option<[f32; 16]>,
+    screen_space_subsurface_scattering_guide: Option<NVSDK_NGX_Resource_VK>,
+    bias: Option<NVSDK_NGX_Resource_VK>,
+    dlss_output: NVSDK_NGX_Resource_VK,
 }
@@ -0,0 +1,256 @@
+use crate::DlssError;
+use ash::vk;
+use std::time::Duration;
+use wgpu::{Device, Surface, hal::api::Vulkan};
+
+/// Low latency mode and frame rate limiter settings for [`DlssReflex::set_sleep_mode`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct DlssReflexSleepMode {
+    /// Enable low latency mode, which delays the start of each frame so that the CPU doesn't run ahead of the GPU.
+    pub low_latency: bool,
+    /// Additionally keep GPU clocks high, trading power for latency.
+    pub boost: bool,
+    /// Minimum time between frames, limiting the frame rate at the driver level. [`Duration::ZERO`] disables the limiter.
+    pub minimum_frame_interval: Duration,
+}
+
+/// A point in a frame's lifetime, reported to the driver by [`DlssReflex::set_marker`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DlssReflexMarker {
+    SimulationStart,
+    SimulationEnd,
+    RenderSubmitStart,
+    RenderSubmitEnd,
+    PresentStart,
+    PresentEnd,
+    /// When input was sampled for the frame.
+    InputSample,
+    /// When a mouse click happened, for measuring latency with the Reflex Latency Analyzer.
+    TriggerFlash,
+}
+
+/// Timestamps of a single frame's markers, returned by [`DlssReflex::frame_reports`].
+///
+/// Times are in microseconds, relative to an unspecified epoch, and are 0 for markers that were not set.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct DlssReflexFrameReport {
+    pub frame_id: u64,
+    pub input_sample_time_us: u64,
+    pub simulation_start_time_us: u64,
+    pub simulation_end_time_us: u64,
+    pub render_submit_start_time_us: u64,
+    pub render_submit_end_time_us: u64,
+    pub present_start_time_us: u64,
+    pub present_end_time_us: u64,
+    pub driver_start_time_us: u64,
+    pub driver_end_time_us: u64,
+    pub os_render_queue_start_time_us: u64,
+    pub os_render_queue_end_time_us: u64,
+    pub gpu_render_start_time_us: u64,
+    pub gpu_render_end_time_us: u64,
+}
+
+/// NVIDIA Reflex low latency mode, frame rate limiter, and latency markers, using `VK_NV_low_latency2`.
+///
+/// The extension is enabled by [`crate::request_device`] when [`crate::FeatureSupport::low_latency_supported`] is true.
+///
+/// wgpu does not enable low latency mode when creating swapchains (`VkSwapchainLatencyCreateInfoNV`),
+/// so some drivers may ignore these settings.
+pub struct DlssReflex {
+    device: Device,
+    low_latency: ash::nv::low_latency2::Device,
+    semaphore: vk::Semaphore,
+    sleep_value: u64,
+}
+
+impl DlssReflex {
+    /// Create a new [`DlssReflex`] object.
+    ///
+    /// Returns [`DlssError::FeatureNotSupported`] if `VK_NV_low_latency2` is not enabled on `device`.
+    pub fn new(device: &Device) -> Result<Self, DlssError> {
+        unsafe {
+            let hal_device = device.as_hal::<Vulkan>().unwrap();
+            if !hal_device
+                .enabled_device_extensions()
+                .contains(&ash::nv::low_latency2::NAME)
+            {
+                return Err(DlssError::FeatureNotSupported);
+            }
+
+            let low_latency = ash::nv::low_latency2::Device::new(
+                hal_device.shared_instance().raw_instance(),
+                hal_device.raw_device(),
+            );
+
+            let mut semaphore_type_info = vk::SemaphoreTypeCreateInfo::default()
+                .semaphore_type(vk::SemaphoreType::TIMELINE)
+                .initial_value(0);
+            let semaphore = hal_device
+                .raw_device()
+                .create_semaphore(
+                    &vk::SemaphoreCreateInfo::default().push_next(&mut semaphore_type_info),
+                    None,
+                )
+                .map_err(|_| DlssError::PlatformError)?;
+
+            Ok(Self {
+                device: device.clone(),
+                low_latency,
+                semaphore,
+                sleep_value: 0,
+            })
+        }
+    }
+
+    /// Configure low latency mode and the frame rate limiter for `surface`.
+    ///
+    /// Must be called again whenever `surface` is reconfigured.
+    pub fn set_sleep_mode(
+        &self,
+        surface: &Surface,
+        sleep_mode: DlssReflexSleepMode,
+    ) -> Result<(), DlssError> {
+        let swapchain = raw_swapchain(surface)?;
+        let sleep_mode_info = vk::LatencySleepModeInfoNV::default()
+            .low_latency_mode(sleep_mode.low_latency)
+            .low_latency_boost(sleep_mode.boost)
+            .minimum_interval_us(
+                sleep_mode
+                    .minimum_frame_interval
+                    .as_micros()
+                    .try_into()
+                    .unwrap_or(u32::MAX),
+            );
+
+        unsafe {
+            self.low_latency
+                .set_latency_sleep_mode(swapchain, Some(&sleep_mode_info))
+                .map_err(|_| DlssError::PlatformError)
+        }
+    }
+
+    /// Block the calling thread until the driver is ready for the next frame to start, according to [`Self::set_sleep_mode`].
+    ///
+    /// Call this once per frame, right before sampling input.
+    pub fn sleep(&mut self, surface: &Surface) -> Result<(), DlssError> {
+        let swapchain = raw_swapchain(surface)?;
+        self.sleep_value += 1;
+
+        unsafe {
+            self.low_latency
+                .latency_sleep(
+                    swapchain,
+                    &vk::LatencySleepInfoNV::default()
+                        .signal_semaphore(self.semaphore)
+                        .value(self.sleep_value),
+                )
+                .map_err(|_| DlssError::PlatformError)?;
+
+            self.device
+                .as_hal::<Vulkan>()
+                .unwrap()
+                .raw_device()
+                .wait_semaphores(
+                    &vk::SemaphoreWaitInfo::default()
+                        .semaphores(&[self.semaphore])
+                        .values(&[self.sleep_value]),
+                    u64::MAX,
+                )
+                .map_err(|_| DlssError::PlatformError)
+        }
+    }
+
+    /// Report that `frame_id` reached `marker`, so that the driver can measure and reduce latency.
+    ///
+    /// `frame_id` should increase by one every frame, and be the same for every marker of a frame.
+    pub fn set_marker(
+        &self,
+        surface: &Surface,
+        frame_id: u64,
+        marker: DlssReflexMarker,
+    ) -> Result<(), DlssError> {
+        let swapchain = raw_swapchain(surface)?;
+        let marker = match marker {
+            DlssReflexMarker::SimulationStart => vk::LatencyMarkerNV::SIMULATION_START,
+            DlssReflexMarker::SimulationEnd => vk::LatencyMarkerNV::SIMULATION_END,
+            DlssReflexMarker::RenderSubmitStart => vk::LatencyMarkerNV::RENDERSUBMIT_START,
+            DlssReflexMarker::RenderSubmitEnd => vk::LatencyMarkerNV::RENDERSUBMIT_END,
+            DlssReflexMarker::PresentStart => vk::LatencyMarkerNV::PRESENT_START,
+            DlssReflexMarker::PresentEnd => vk::LatencyMarkerNV::PRESENT_END,
+            DlssReflexMarker::InputSample => vk::LatencyMarkerNV::INPUT_SAMPLE,
+            DlssReflexMarker::TriggerFlash => vk::LatencyMarkerNV::TRIGGER_FLASH,
+        };
+
+        unsafe {
+            self.low_latency.set_latency_marker(
+                swapchain,
+                &vk::SetLatencyMarkerInfoNV::default()
+                    .present_id(frame_id)
+                    .marker(marker),
+            );
+        }
+        Ok(())
+    }
+
+    /// Timestamps of the markers of recent frames, oldest first, for latency HUDs and telemetry.
+    pub fn frame_reports(
+        &self,
+        surface: &Surface,
+    ) -> Result<Vec<DlssReflexFrameReport>, DlssError> {
+        let swapchain = raw_swapchain(surface)?;
+
+        unsafe {
+            let mut count_info = vk::GetLatencyMarkerInfoNV::default();
+            self.low_latency
+                .get_latency_timings(swapchain, &mut count_info);
+
+            let mut timings =
+                vec![vk::LatencyTimingsFrameReportNV::default(); count_info.timing_count as usize];
+            self.low_latency.get_latency_timings(
+                swapchain,
+                &mut vk::GetLatencyMarkerInfoNV::default().timings(&mut timings),
+            );
+
+            Ok(timings
+                .iter()
+                .map(|timing| DlssReflexFrameReport {
+                    frame_id: timing.present_id,
+                    input_sample_time_us: timing.input_sample_time_us,
+                    simulation_start_time_us: timing.sim_start_time_us,
+                    simulation_end_time_us: timing.sim_end_time_us,
+                    render_submit_start_time_us: timing.render_submit_start_time_us,
+                    render_submit_end_time_us: timing.render_submit_end_time_us,
+                    present_start_time_us: timing.present_start_time_us,
+                    present_end_time_us: timing.present_end_time_us,
+                    driver_start_time_us: timing.driver_start_time_us,
+                    driver_end_time_us: timing.driver_end_time_us,
+                    os_render_queue_start_time_us: timing.os_render_queue_start_time_us,
+                    os_render_queue_end_time_us: timing.os_render_queue_end_time_us,
+                    gpu_render_start_time_us: timing.gpu_render_start_time_us,
+                    gpu_render_end_time_us: timing.gpu_render_end_time_us,
+                })
+                .collect())
+        }
+    }
+}
+
+fn raw_swapchain(surface: &Surface) -> Result<vk::SwapchainKHR, DlssError> {
+    unsafe {
+        surface
+            .as_hal::<Vulkan>()
+            .and_then(|surface| surface.raw_native_swapchain())
+            .ok_or(DlssError::SurfaceNotConfigured)
+    }
+}
+
+impl Drop for DlssReflex {
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .as_hal::<Vulkan>()
+                .unwrap()
+                .raw_device()
+                .destroy_semaphore(self.semaphore, None);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    DlssError, DlssFeatureFlags, DlssPerfQualityMode, DlssSdk,
+    super_resolution::DlssSuperResolution,
+};
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use wgpu::{Device, Queue};
+
+/// Wraps a [`DlssSuperResolution`] context, recreating it whenever the output resolution changes.
+///
+/// Replaced contexts are kept alive until the queue has finished the work submitted before the resize, as reported by
+/// [`Queue::on_submitted_work_done`], and are then destroyed without waiting for the device to be idle.
+/// Until then, both the old and new contexts use VRAM.
+///
+/// Work using the old context must have been submitted before the [`Self::update`] call that replaces it,
+/// and the device must be polled, e.g. by presenting, for replaced contexts to be destroyed.
+/// Replaced contexts still alive when the resizer is dropped wait for the device to be idle.
+pub struct DlssSuperResolutionResizer {
+    perf_quality_mode: DlssPerfQualityMode,
+    feature_flags: DlssFeatureFlags,
+    sdk: Arc<Mutex<DlssSdk>>,
+    context: Option<DlssSuperResolution>,
+    retired_contexts: VecDeque<(DlssSuperResolution, Arc<AtomicBool>)>,
+}
+
+impl DlssSuperResolutionResizer {
+    /// Create a new [`DlssSuperResolutionResizer`]. No context is created until [`Self::update`] is called.
+    pub fn new(
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        sdk: Arc<Mutex<DlssSdk>>,
+    ) -> Self {
+        Self {
+            perf_quality_mode,
+            feature_flags,
+            sdk,
+            context: None,
+            retired_contexts: VecDeque::new(),
+        }
+    }
+
+    /// Call once per frame with the current output size, e.g. the surface size.
+    ///
+    /// Returns the context for `upscaled_resolution`, creating a new one if the resolution changed since the last call.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn update(
+        &mut self,
+        upscaled_resolution: [u32; 2],
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<&mut DlssSuperResolution, DlssError> {
+        self.retired_contexts.retain_mut(|(context, completed)| {
+            context.gpu_work_done = completed.load(Ordering::Acquire);
+            !context.gpu_work_done
+        });
+
+        if let Some(context) = self
+            .context
+            .take_if(|context| context.upscaled_resolution() != upscaled_resolution)
+        {
+            let completed = Arc::new(AtomicBool::new(false));
+            let completed_clone = Arc::clone(&completed);
+            queue.on_submitted_work_done(move || completed_clone.store(true, Ordering::Release));
+            self.retired_contexts.push_back((context, completed));
+        }
+
+        match &mut self.context {
+            Some(context) => Ok(context),
+            context @ None => {
+                let mut new_context = DlssSuperResolution::new(
+                    upscaled_resolution,
+                    self.perf_quality_mode,
+                    self.feature_flags,
+                    Arc::clone(&self.sdk),
+                    device,
+                    queue,
+                )?;
+                if let Some((previous_context, _)) = self.retired_contexts.back() {
+                    new_context.counters = previous_context.counters.recreated();
+                }
+                Ok(context.insert(new_context))
+            }
+        }
+    }
+
+    /// The current context, if [`Self::update`] has been called.
+    pub fn context(&self) -> Option<&DlssSuperResolution> {
+        self.context.as_ref()
+    }
+
+    /// Like [`Self::context`], but returns a mutable reference for rendering.
+    pub fn context_mut(&mut self) -> Option<&mut DlssSuperResolution> {
+        self.context.as_mut()
+    }
+}
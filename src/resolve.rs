@@ -0,0 +1,187 @@
+use std::{borrow::Cow, collections::HashMap};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindingResource, Color, ColorTargetState, ColorWrites,
+    CommandEncoder, CompareFunction, DepthStencilState, Device, FragmentState, LoadOp,
+    MultisampleState, Operations, PipelineCompilationOptions, PrimitiveState,
+    RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+    RenderPipeline, RenderPipelineDescriptor, ShaderModule, ShaderModuleDescriptor, ShaderSource,
+    StoreOp, TextureFormat, TextureView, VertexState,
+};
+
+/// Resolves multisampled color, depth, and motion vectors into the single-sampled textures DLSS requires,
+/// for renderers using MSAA.
+///
+/// Color is averaged across samples. Depth is resolved to the minimum or maximum sample according to [`DlssDepthResolve`],
+/// and motion vectors are taken from the same sample as the depth, so that they match the surface DLSS sees.
+pub struct DlssMsaaResolve {
+    device: Device,
+    shader: ShaderModule,
+    pipelines: HashMap<PipelineKey, RenderPipeline>,
+}
+
+/// How [`DlssMsaaResolve`] picks the depth of each pixel from its samples.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DlssDepthResolve {
+    /// The smallest depth, which is the closest sample with a standard depth buffer.
+    Min,
+    /// The largest depth, which is the closest sample with a reverse-Z depth buffer.
+    #[default]
+    Max,
+}
+
+/// The textures read or written by [`DlssMsaaResolve::resolve`].
+#[derive(Clone, Copy, Debug)]
+pub struct DlssMsaaResolveTextures<'a> {
+    pub color: &'a TextureView,
+    pub depth: &'a TextureView,
+    pub motion_vectors: &'a TextureView,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    color_format: TextureFormat,
+    depth_format: TextureFormat,
+    motion_vector_format: TextureFormat,
+    depth_resolve: DlssDepthResolve,
+}
+
+impl DlssMsaaResolve {
+    /// Create a new [`DlssMsaaResolve`]. Pipelines are created lazily for each combination of target formats.
+    pub fn new(device: &Device) -> Self {
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("dlss_msaa_resolve_shader"),
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("resolve.wgsl"))),
+        });
+
+        Self {
+            device: device.clone(),
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Encode a render pass into `command_encoder` that resolves the multisampled `source` textures into `target`.
+    ///
+    /// `source` textures must share a sample count, and have been created with [`wgpu::TextureUsages::TEXTURE_BINDING`].
+    /// `target` textures must be single-sampled, the same size as `source`, and have been created with
+    /// [`wgpu::TextureUsages::RENDER_ATTACHMENT`]. The target depth texture must have a depth format, and its stencil is left untouched.
+    pub fn resolve(
+        &mut self,
+        source: DlssMsaaResolveTextures,
+        target: DlssMsaaResolveTextures,
+        depth_resolve: DlssDepthResolve,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let key = PipelineKey {
+            color_format: target.color.texture().format(),
+            depth_format: target.depth.texture().format(),
+            motion_vector_format: target.motion_vectors.texture().format(),
+            depth_resolve,
+        };
+        let pipeline = self
+            .pipelines
+            .entry(key)
+            .or_insert_with(|| create_pipeline(&self.device, &self.shader, key));
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("dlss_msaa_resolve_bind_group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(source.color),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(source.depth),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(source.motion_vectors),
+                },
+            ],
+        });
+
+        let color_attachment = |view| {
+            Some(RenderPassColorAttachment {
+                view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::TRANSPARENT),
+                    store: StoreOp::Store,
+                },
+            })
+        };
+        let mut pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("dlss_msaa_resolve"),
+            color_attachments: &[
+                color_attachment(target.color),
+                color_attachment(target.motion_vectors),
+            ],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: target.depth,
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(0.0),
+                    store: StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            ..Default::default()
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+fn create_pipeline(device: &Device, shader: &ShaderModule, key: PipelineKey) -> RenderPipeline {
+    let color_target = |format| {
+        Some(ColorTargetState {
+            format,
+            blend: None,
+            write_mask: ColorWrites::ALL,
+        })
+    };
+
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("dlss_msaa_resolve_pipeline"),
+        layout: None,
+        vertex: VertexState {
+            module: shader,
+            entry_point: Some("vertex"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(FragmentState {
+            module: shader,
+            entry_point: Some("fragment"),
+            compilation_options: PipelineCompilationOptions {
+                constants: &[(
+                    "DEPTH_MAX",
+                    if key.depth_resolve == DlssDepthResolve::Max {
+                        1.0
+                    } else {
+                        0.0
+                    },
+                )],
+                ..Default::default()
+            },
+            targets: &[
+                color_target(key.color_format),
+                color_target(key.motion_vector_format),
+            ],
+        }),
+        primitive: PrimitiveState::default(),
+        depth_stencil: Some(DepthStencilState {
+            format: key.depth_format,
+            depth_write_enabled: Some(true),
+            depth_compare: Some(CompareFunction::Always),
+            stencil: Default::default(),
+            bias: Default::default(),
+        }),
+        multisample: MultisampleState::default(),
+        multiview_mask: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,43 @@
+// Whether the resolved depth is the maximum of the samples, rather than the minimum
+override DEPTH_MAX: bool;
+
+@group(0) @binding(0) var color: texture_multisampled_2d<f32>;
+@group(0) @binding(1) var depth: texture_depth_multisampled_2d;
+@group(0) @binding(2) var motion_vectors: texture_multisampled_2d<f32>;
+
+struct FragmentOutput {
+    @builtin(frag_depth) depth: f32,
+    @location(0) color: vec4<f32>,
+    @location(1) motion_vector: vec4<f32>,
+}
+
+@vertex
+fn vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // Fullscreen triangle
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+}
+
+@fragment
+fn fragment(@builtin(position) position: vec4<f32>) -> FragmentOutput {
+    let coordinates = vec2<u32>(position.xy);
+    let sample_count = textureNumSamples(color);
+
+    var color_sum = vec4<f32>(0.0);
+    var selected_depth = textureLoad(depth, coordinates, 0);
+    var selected_sample = 0u;
+    for (var sample = 0u; sample < sample_count; sample++) {
+        color_sum += textureLoad(color, coordinates, sample);
+
+        let sample_depth = textureLoad(depth, coordinates, sample);
+        if (DEPTH_MAX && sample_depth > selected_depth) || (!DEPTH_MAX && sample_depth < selected_depth) {
+            selected_depth = sample_depth;
+            selected_sample = sample;
+        }
+    }
+
+    // Motion vectors come from the same sample as the depth, so that edges move with the surface DLSS sees
+    let motion_vector = textureLoad(motion_vectors, coordinates, selected_sample);
+
+    return FragmentOutput(selected_depth, color_sum / f32(sample_count), motion_vector);
+}
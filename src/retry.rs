@@ -0,0 +1,146 @@
+use crate::DlssError;
+use std::{thread, time::Duration};
+
+/// Retries an operation, such as creating a DLSS context, while it fails with a [transient](DlssError::is_transient) error.
+///
+/// The calling thread sleeps between attempts, so this is best suited to loading screens and background threads.
+/// ```compile_fail
+/// let dlss = DlssRetryPolicy::default()
+///     .retry(|| DlssSuperResolution::new(upscaled_resolution, mode, flags, Arc::clone(&sdk), &device, &queue))?;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DlssRetryPolicy {
+    /// Total number of attempts, including the first. Values below 1 are treated as 1.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Factor the delay is multiplied by after each retry. Negative and NaN values are treated as 1.
+    pub backoff_multiplier: f32,
+    /// Upper limit for the delay between attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for DlssRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+            backoff_multiplier: 2.0,
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl DlssRetryPolicy {
+    /// Call `operation` until it succeeds, fails with a fatal error, or runs out of attempts, returning the last result.
+    pub fn retry<T>(
+        &self,
+        mut operation: impl FnMut() -> Result<T, DlssError>,
+    ) -> Result<T, DlssError> {
+        let mut backoff = self.initial_backoff.min(self.max_backoff);
+        for _ in 1..self.max_attempts {
+            match operation() {
+                Err(error) if error.is_transient() => {
+                    thread::sleep(backoff);
+                    backoff = self.next_backoff(backoff);
+                }
+                result => return result,
+            }
+        }
+        operation()
+    }
+
+    /// The delay after `backoff`, saturating at [`Self::max_backoff`].
+    fn next_backoff(&self, backoff: Duration) -> Duration {
+        let multiplier = if self.backoff_multiplier >= 0.0 {
+            self.backoff_multiplier
+        } else {
+            1.0
+        };
+        Duration::try_from_secs_f64(backoff.as_secs_f64() * multiplier as f64)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backoffs(policy: DlssRetryPolicy) -> Vec<Duration> {
+        let mut backoff = policy.initial_backoff.min(policy.max_backoff);
+        (0..5)
+            .map(|_| {
+                let current = backoff;
+                backoff = policy.next_backoff(backoff);
+                current
+            })
+            .collect()
+    }
+
+    #[test]
+    fn backoff_sequence() {
+        let policy = DlssRetryPolicy {
+            max_backoff: Duration::from_millis(50),
+            ..Default::default()
+        };
+        assert_eq!(
+            backoffs(policy),
+            [10, 20, 40, 50, 50].map(Duration::from_millis)
+        );
+    }
+
+    #[test]
+    fn invalid_backoff_multiplier() {
+        for backoff_multiplier in [-2.0, f32::NAN] {
+            let policy = DlssRetryPolicy {
+                backoff_multiplier,
+                ..Default::default()
+            };
+            assert_eq!(backoffs(policy), [Duration::from_millis(10); 5]);
+        }
+
+        // Overflowing Duration saturates instead of panicking
+        for backoff_multiplier in [f32::MAX, f32::INFINITY] {
+            let policy = DlssRetryPolicy {
+                backoff_multiplier,
+                max_backoff: Duration::MAX,
+                ..Default::default()
+            };
+            assert_eq!(backoffs(policy)[2..], [Duration::MAX; 3]);
+        }
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let policy = DlssRetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..Default::default()
+        };
+
+        let mut attempts = 0;
+        let result = policy.retry(|| -> Result<(), _> {
+            attempts += 1;
+            Err(DlssError::OutOfGPUMemory)
+        });
+        assert_eq!((result, attempts), (Err(DlssError::OutOfGPUMemory), 3));
+
+        let mut attempts = 0;
+        let result = policy.retry(|| -> Result<(), _> {
+            attempts += 1;
+            Err(DlssError::InvalidParameters)
+        });
+        assert_eq!((result, attempts), (Err(DlssError::InvalidParameters), 1));
+
+        let mut attempts = 0;
+        let result = policy.retry(|| {
+            attempts += 1;
+            if attempts < 2 {
+                Err(DlssError::OutOfGPUMemory)
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result, Ok(2));
+    }
+}
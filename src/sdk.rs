@@ -1,66 +1,256 @@
-use crate::{feature_info::with_feature_info, nvsdk_ngx::*};
+use crate::{
+    DlssInitOptions, DlssLoggingLevel, DlssPerfQualityMode, DlssRayReconstructionPreset,
+    DlssRenderPresets, DlssSuperResolutionPreset, feature_info::with_feature_info, nvsdk_ngx::*,
+    super_resolution::DlssSuperResolution,
+};
+use ash::vk::Handle;
 use std::{
+    path::PathBuf,
     ptr,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, PoisonError},
     thread,
 };
 use uuid::Uuid;
-use wgpu::{Device, hal::api::Vulkan};
+use wgpu::{Device, Queue, hal::api::Vulkan};
+
+/// Raw `VkDevice` handles that currently have a [`DlssSdk`].
+///
+/// Also held while initializing and shutting down NGX, which modifies global state shared by every device in the process.
+static INITIALIZED_DEVICES: Mutex<Vec<u64>> = Mutex::new(Vec::new());
 
 /// Application-wide DLSS object.
+///
+/// DLSS contexts hold a strong reference to the SDK, so the SDK is only destroyed once every context created from it has been dropped.
+///
+/// The SDK holds a strong reference to its [`Device`], so the device is likewise kept alive by the SDK and any lingering contexts.
+/// Drop cached contexts, e.g. via [`crate::DlssContextManager::evict_unused`], before tearing down the device.
 pub struct DlssSdk {
     pub(crate) parameters: *mut NVSDK_NGX_Parameter,
     pub(crate) device: Device,
+    /// Incremented by [`Self::reinitialize`], invalidating contexts created before it.
+    pub(crate) generation: u64,
+    options: DlssInitOptions,
+    pub(crate) super_resolution_presets: DlssRenderPresets<DlssSuperResolutionPreset>,
+    pub(crate) ray_reconstruction_presets: DlssRenderPresets<DlssRayReconstructionPreset>,
 }
 
 impl DlssSdk {
     /// Creates the DLSS SDK.
     ///
-    /// This should be done once per application.
-    pub fn new(project_id: Uuid, device: Device) -> Result<Arc<Mutex<Self>>, DlssError> {
-        check_for_updates(project_id);
+    /// This should be done once per application. Only one SDK may exist per [`Device`] at a time,
+    /// and creating a second one returns [`DlssError::AlreadyInitialized`].
+    ///
+    /// Separate devices, including devices from different wgpu [`wgpu::Instance`]s, may each have their own SDK.
+    pub fn new(options: &DlssInitOptions, device: Device) -> Result<Arc<Mutex<Self>>, DlssError> {
+        let raw_device = unsafe { device.as_hal::<Vulkan>().unwrap().raw_device().handle() };
+        let mut initialized_devices = INITIALIZED_DEVICES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if initialized_devices.contains(&raw_device.as_raw()) {
+            return Err(DlssError::AlreadyInitialized);
+        }
+
+        let parameters = unsafe { init_ngx(options, &device)? };
+
+        initialized_devices.push(raw_device.as_raw());
+
+        // Runs once this function releases the lock
+        if options.check_for_updates {
+            check_for_updates(options.clone());
+        }
+
+        Ok(Arc::new(Mutex::new(Self {
+            parameters,
+            device,
+            generation: 0,
+            options: options.clone(),
+            super_resolution_presets: DlssRenderPresets::default(),
+            ray_reconstruction_presets: DlssRenderPresets::default(),
+        })))
+    }
+
+    /// Start building a [`DlssSdk`] with the given project ID, as an alternative to [`Self::new`].
+    pub fn builder(project_id: Uuid, device: Device) -> DlssSdkBuilder {
+        DlssSdkBuilder {
+            options: DlssInitOptions::new(project_id),
+            device,
+        }
+    }
+
+    /// Shuts down NGX and initializes it again using `options`, for example to load a different DLSS library
+    /// from [`DlssInitOptions::shared_library_paths`] without restarting the application.
+    ///
+    /// All existing DLSS contexts created from this SDK are released. Rendering with them returns
+    /// [`DlssError::SdkReinitialized`], and they must be recreated.
+    ///
+    /// If initialization fails, the SDK is left shut down, and this function can be called again.
+    pub fn reinitialize(&mut self, options: &DlssInitOptions) -> Result<(), DlssError> {
+        let _initialized_devices = INITIALIZED_DEVICES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
 
-        let mut parameters = ptr::null_mut();
         unsafe {
-            let hal_device = device.as_hal::<Vulkan>().unwrap();
-            let shared_instance = hal_device.shared_instance();
-            let raw_instance = shared_instance.raw_instance();
-
-            with_feature_info(project_id, Default::default(), |feature_info| {
-                check_ngx_result(NVSDK_NGX_VULKAN_Init_with_ProjectID(
-                    feature_info.Identifier.v.ProjectDesc.ProjectId,
-                    NVSDK_NGX_EngineType_NVSDK_NGX_ENGINE_TYPE_CUSTOM,
-                    feature_info.Identifier.v.ProjectDesc.EngineVersion,
-                    feature_info.ApplicationDataPath,
-                    raw_instance.handle(),
-                    hal_device.raw_physical_device(),
-                    hal_device.raw_device().handle(),
-                    shared_instance.entry().static_fn().get_instance_proc_addr,
-                    raw_instance.fp_v1_0().get_device_proc_addr,
-                    feature_info.FeatureInfo,
-                    NVSDK_NGX_Version_NVSDK_NGX_Version_API,
-                ))
-            })?;
-
-            check_ngx_result(NVSDK_NGX_VULKAN_GetCapabilityParameters(&mut parameters))?;
-
-            let mut dlss_supported = 0;
-            let result = check_ngx_result(NVSDK_NGX_Parameter_GetI(
-                parameters,
-                NVSDK_NGX_Parameter_SuperSampling_Available.as_ptr().cast(),
-                &mut dlss_supported,
-            ));
-            if result.is_err() {
-                check_ngx_result(NVSDK_NGX_VULKAN_DestroyParameters(parameters))?;
-                result?;
-            }
-            if dlss_supported == 0 {
-                check_ngx_result(NVSDK_NGX_VULKAN_DestroyParameters(parameters))?;
-                return Err(DlssError::FeatureNotSupported);
+            let hal_device = self.device.as_hal::<Vulkan>().unwrap();
+            hal_device
+                .raw_device()
+                .device_wait_idle()
+                .map_err(|_| DlssError::PlatformError)?;
+
+            if !self.parameters.is_null() {
+                check_ngx_result(NVSDK_NGX_VULKAN_DestroyParameters(self.parameters))?;
+                // Existing contexts are invalid from here on, even if shutting down fails
+                self.parameters = ptr::null_mut();
+                self.generation += 1;
+                check_ngx_result(NVSDK_NGX_VULKAN_Shutdown1(hal_device.raw_device().handle()))?;
             }
+
+            self.parameters = init_ngx(options, &self.device)?;
+        }
+        self.options = options.clone();
+
+        Ok(())
+    }
+
+    /// Check for, and download, over-the-air updates to the DLSS Super Resolution and Ray Reconstruction models, blocking until done.
+    ///
+    /// This also happens in the background after [`Self::new`] if [`DlssInitOptions::check_for_updates`] is set.
+    /// While updating, initializing or shutting down any [`DlssSdk`] blocks.
+    /// Downloaded models are only used once NGX is initialized again, via [`Self::reinitialize`] or on the next run.
+    /// NGX does not report whether a newer model was found.
+    pub fn update_features(&self) -> Result<(), DlssError> {
+        update_features(&self.options)
+    }
+
+    /// Create a [`DlssSuperResolution`] context for each of `perf_quality_modes`, e.g. during a loading screen,
+    /// so that switching to one of those modes later doesn't hitch.
+    ///
+    /// Keep the returned contexts to switch between modes instantly, or drop them to only warm up NGX's internal caches.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn warm_up(
+        sdk: &Arc<Mutex<Self>>,
+        upscaled_resolution: [u32; 2],
+        perf_quality_modes: &[DlssPerfQualityMode],
+        feature_flags: DlssFeatureFlags,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Vec<DlssSuperResolution>, DlssError> {
+        perf_quality_modes
+            .iter()
+            .map(|&perf_quality_mode| {
+                DlssSuperResolution::new(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    Arc::clone(sdk),
+                    device,
+                    queue,
+                )
+            })
+            .collect()
+    }
+
+    /// Pin the model used by DLSS Super Resolution contexts created from now on, for every quality mode, e.g. to compare presets.
+    ///
+    /// Existing contexts keep the preset they were created with, see [`DlssSuperResolution::preset`].
+    pub fn set_super_resolution_preset(&mut self, preset: DlssSuperResolutionPreset) {
+        self.super_resolution_presets = DlssRenderPresets::all(preset);
+    }
+
+    /// Like [`Self::set_super_resolution_preset`], but with a separate preset for each quality mode.
+    pub fn set_super_resolution_presets(
+        &mut self,
+        presets: DlssRenderPresets<DlssSuperResolutionPreset>,
+    ) {
+        self.super_resolution_presets = presets;
+    }
+
+    /// The presets used by new DLSS Super Resolution contexts.
+    pub fn super_resolution_presets(&self) -> DlssRenderPresets<DlssSuperResolutionPreset> {
+        self.super_resolution_presets
+    }
+
+    /// Pin the model used by DLSS Ray Reconstruction contexts created from now on, for every quality mode, e.g. to compare presets.
+    ///
+    /// Existing contexts keep the preset they were created with, see [`crate::ray_reconstruction::DlssRayReconstruction::preset`].
+    pub fn set_ray_reconstruction_preset(&mut self, preset: DlssRayReconstructionPreset) {
+        self.ray_reconstruction_presets = DlssRenderPresets::all(preset);
+    }
+
+    /// Like [`Self::set_ray_reconstruction_preset`], but with a separate preset for each quality mode.
+    pub fn set_ray_reconstruction_presets(
+        &mut self,
+        presets: DlssRenderPresets<DlssRayReconstructionPreset>,
+    ) {
+        self.ray_reconstruction_presets = presets;
+    }
+
+    /// The presets used by new DLSS Ray Reconstruction contexts.
+    pub fn ray_reconstruction_presets(&self) -> DlssRenderPresets<DlssRayReconstructionPreset> {
+        self.ray_reconstruction_presets
+    }
+
+    /// Availability of DLSS Super Resolution, as reported by NGX.
+    ///
+    /// Complements [`crate::FeatureSupport::super_resolution_supported`], which only checks for Vulkan extension support.
+    pub fn super_resolution_availability(&self) -> DlssFeatureAvailability {
+        self.ngx_feature_availability(DlssNgxFeature::SuperSampling)
+    }
+
+    /// Availability of DLSS Ray Reconstruction, as reported by NGX.
+    ///
+    /// Complements [`crate::FeatureSupport::ray_reconstruction_supported`], which only checks for Vulkan extension support.
+    pub fn ray_reconstruction_availability(&self) -> DlssFeatureAvailability {
+        self.ngx_feature_availability(DlssNgxFeature::SuperSamplingDenoising)
+    }
+
+    /// Availability of every NGX feature known to this crate, including those it does not wrap, for diagnostics.
+    pub fn ngx_features(&self) -> Vec<(DlssNgxFeature, DlssFeatureAvailability)> {
+        DlssNgxFeature::ALL
+            .into_iter()
+            .map(|feature| (feature, self.ngx_feature_availability(feature)))
+            .collect()
+    }
+
+    /// Availability of any NGX feature, as reported by the capability parameters of the installed driver.
+    pub fn ngx_feature_availability(&self, feature: DlssNgxFeature) -> DlssFeatureAvailability {
+        let parameter = |name: &str| format!("{}.{name}\0", feature.parameter_prefix());
+        let get_i = |name: &str| {
+            let mut value = 0;
+            let name = parameter(name);
+            let result = unsafe {
+                NVSDK_NGX_Parameter_GetI(self.parameters, name.as_ptr().cast(), &mut value)
+            };
+            (result == NVSDK_NGX_Result_Success).then_some(value)
+        };
+        let get_ui = |name: &str| {
+            let mut value = 0;
+            let name = parameter(name);
+            let result = unsafe {
+                NVSDK_NGX_Parameter_GetUI(self.parameters, name.as_ptr().cast(), &mut value)
+            };
+            (result == NVSDK_NGX_Result_Success).then_some(value)
+        };
+
+        let init_result_code = get_i("FeatureInitResult").map(|result| result as u32);
+        DlssFeatureAvailability {
+            available: get_i("Available").is_some_and(|value| value != 0),
+            needs_updated_driver: get_i("NeedsUpdatedDriver").is_some_and(|value| value != 0),
+            min_driver_version: get_ui("MinDriverVersionMajor")
+                .zip(get_ui("MinDriverVersionMinor"))
+                .map(|(major, minor)| [major, minor]),
+            init_result: init_result_code.and_then(try_check_ngx_result),
+            init_result_code,
+            snippet_version: get_ui("SnippetVersion"),
         }
+    }
 
-        Ok(Arc::new(Mutex::new(Self { parameters, device })))
+    /// The underlying NGX capability parameters, for calling NGX functions this crate does not wrap.
+    ///
+    /// # Safety
+    /// The parameters must not be destroyed. They are invalidated by [`Self::reinitialize`].
+    pub unsafe fn raw_parameters(&self) -> *mut NVSDK_NGX_Parameter {
+        self.parameters
     }
 
     /// Returns the number of bytes of VRAM allocated by DLSS.
@@ -73,41 +263,246 @@ impl DlssSdk {
     }
 }
 
-fn check_for_updates(project_id: Uuid) {
+/// Initializes NGX for `device`, returning its capability parameters.
+unsafe fn init_ngx(
+    options: &DlssInitOptions,
+    device: &Device,
+) -> Result<*mut NVSDK_NGX_Parameter, DlssError> {
+    let mut parameters = ptr::null_mut();
+    unsafe {
+        let hal_device = device.as_hal::<Vulkan>().unwrap();
+        let shared_instance = hal_device.shared_instance();
+        let raw_instance = shared_instance.raw_instance();
+
+        with_feature_info(options, Default::default(), |feature_info| {
+            check_ngx_result(NVSDK_NGX_VULKAN_Init_with_ProjectID(
+                feature_info.Identifier.v.ProjectDesc.ProjectId,
+                NVSDK_NGX_EngineType_NVSDK_NGX_ENGINE_TYPE_CUSTOM,
+                feature_info.Identifier.v.ProjectDesc.EngineVersion,
+                feature_info.ApplicationDataPath,
+                raw_instance.handle(),
+                hal_device.raw_physical_device(),
+                hal_device.raw_device().handle(),
+                shared_instance.entry().static_fn().get_instance_proc_addr,
+                raw_instance.fp_v1_0().get_device_proc_addr,
+                feature_info.FeatureInfo,
+                NVSDK_NGX_Version_NVSDK_NGX_Version_API,
+            ))
+        })?;
+
+        let result = check_ngx_result(NVSDK_NGX_VULKAN_GetCapabilityParameters(&mut parameters))
+            .and_then(|()| {
+                let mut dlss_supported = 0;
+                let mut result = check_ngx_result(NVSDK_NGX_Parameter_GetI(
+                    parameters,
+                    NVSDK_NGX_Parameter_SuperSampling_Available.as_ptr().cast(),
+                    &mut dlss_supported,
+                ));
+                if result.is_ok() && dlss_supported == 0 {
+                    result = Err(DlssError::FeatureNotSupported);
+                }
+                if result.is_err() {
+                    check_ngx_result(NVSDK_NGX_VULKAN_DestroyParameters(parameters))?;
+                }
+                result
+            });
+
+        // Leave NGX shut down on failure, so that initialization can be retried
+        if result.is_err() {
+            NVSDK_NGX_VULKAN_Shutdown1(hal_device.raw_device().handle());
+        }
+        result?;
+    }
+
+    Ok(parameters)
+}
+
+fn check_for_updates(options: DlssInitOptions) {
     thread::spawn(move || {
-        with_feature_info(
-            project_id,
-            NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling,
-            |feature_info| unsafe {
-                NVSDK_NGX_UpdateFeature(&feature_info.Identifier, feature_info.FeatureID);
-            },
-        );
-        with_feature_info(
-            project_id,
-            NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction,
-            |feature_info| unsafe {
-                NVSDK_NGX_UpdateFeature(&feature_info.Identifier, feature_info.FeatureID);
-            },
-        );
+        let _ = update_features(&options);
     });
 }
 
+/// Updates every feature, even if an earlier one fails, returning the first error.
+///
+/// Blocks until no other thread is initializing or shutting down NGX, as updating shares its global state.
+fn update_features(options: &DlssInitOptions) -> Result<(), DlssError> {
+    let _initialized_devices = INITIALIZED_DEVICES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    [
+        NVSDK_NGX_Feature_NVSDK_NGX_Feature_SuperSampling,
+        NVSDK_NGX_Feature_NVSDK_NGX_Feature_RayReconstruction,
+    ]
+    .map(|feature| {
+        with_feature_info(options, feature, |feature_info| unsafe {
+            check_ngx_result(NVSDK_NGX_UpdateFeature(
+                &feature_info.Identifier,
+                feature_info.FeatureID,
+            ))
+        })
+    })
+    .into_iter()
+    .collect()
+}
+
 impl Drop for DlssSdk {
     fn drop(&mut self) {
         unsafe {
             let hal_device = self.device.as_hal::<Vulkan>().unwrap();
-            hal_device
-                .raw_device()
-                .device_wait_idle()
-                .expect("Failed to wait for idle device when destroying DlssSdk");
+            let wait_result = hal_device.raw_device().device_wait_idle();
+            let mut initialized_devices = INITIALIZED_DEVICES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            // Parameters are null if a reinitialization failed, in which case NGX is already shut down or failed to shut down
+            let (destroy_result, shutdown_result) = if self.parameters.is_null() {
+                (Ok(()), Ok(()))
+            } else {
+                (
+                    check_ngx_result(NVSDK_NGX_VULKAN_DestroyParameters(self.parameters)),
+                    check_ngx_result(NVSDK_NGX_VULKAN_Shutdown1(hal_device.raw_device().handle())),
+                )
+            };
+
+            let raw_device = hal_device.raw_device().handle().as_raw();
+            initialized_devices.retain(|device| *device != raw_device);
+            drop(initialized_devices);
 
-            check_ngx_result(NVSDK_NGX_VULKAN_DestroyParameters(self.parameters))
-                .expect("Failed to destroy DlssSdk parameters");
-            check_ngx_result(NVSDK_NGX_VULKAN_Shutdown1(hal_device.raw_device().handle()))
-                .expect("Failed to destroy DlssSdk");
+            // Panicking while already unwinding would abort
+            if !thread::panicking() {
+                wait_result.expect("Failed to wait for idle device when destroying DlssSdk");
+                destroy_result.expect("Failed to destroy DlssSdk parameters");
+                shutdown_result.expect("Failed to destroy DlssSdk");
+            }
         }
     }
 }
 
+/// Builder for [`DlssSdk`], created by [`DlssSdk::builder`].
+///
+/// The resulting options should match those passed to [`crate::create_instance`] and [`crate::request_device`], see [`Self::options`].
+pub struct DlssSdkBuilder {
+    options: DlssInitOptions,
+    device: Device,
+}
+
+impl DlssSdkBuilder {
+    /// See [`DlssInitOptions::logging_level`].
+    pub fn logging_level(mut self, logging_level: DlssLoggingLevel) -> Self {
+        self.options.logging_level = logging_level;
+        self
+    }
+
+    /// Add a directory to [`DlssInitOptions::shared_library_paths`].
+    pub fn shared_library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.options.shared_library_paths.push(path.into());
+        self
+    }
+
+    /// See [`DlssInitOptions::check_for_updates`].
+    pub fn check_for_updates(mut self, check_for_updates: bool) -> Self {
+        self.options.check_for_updates = check_for_updates;
+        self
+    }
+
+    /// See [`DlssInitOptions::application_data_path`].
+    pub fn application_data_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.options.application_data_path = Some(path.into());
+        self
+    }
+
+    /// See [`DlssInitOptions::engine_version`].
+    pub fn engine_version(mut self, engine_version: impl Into<String>) -> Self {
+        self.options.engine_version = engine_version.into();
+        self
+    }
+
+    /// The options collected so far.
+    pub fn options(&self) -> &DlssInitOptions {
+        &self.options
+    }
+
+    /// Create the [`DlssSdk`]. See [`DlssSdk::new`].
+    pub fn build(self) -> Result<Arc<Mutex<DlssSdk>>, DlssError> {
+        DlssSdk::new(&self.options, self.device)
+    }
+}
+
+/// An NGX feature that may be exposed by the installed driver, for [`DlssSdk::ngx_features`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DlssNgxFeature {
+    /// DLSS Super Resolution.
+    SuperSampling,
+    /// DLSS Ray Reconstruction.
+    SuperSamplingDenoising,
+    /// DLSS Frame Generation.
+    FrameGeneration,
+    /// RTX Dynamic Vibrance, an AI color and saturation filter.
+    DeepDvc,
+    /// AI upscaling of images decoded at a lower resolution.
+    DeepResolve,
+    /// AI image signal processing for camera input.
+    ImageSignalProcessing,
+    /// AI upscaling of still images.
+    ImageSuperResolution,
+    /// AI removal and filling in of image regions.
+    InPainting,
+    /// AI frame interpolation for video.
+    SlowMotion,
+    /// RTX Video Super Resolution, AI upscaling of video.
+    VideoSuperResolution,
+}
+
+impl DlssNgxFeature {
+    /// Every feature, in declaration order.
+    pub const ALL: [Self; 10] = [
+        Self::SuperSampling,
+        Self::SuperSamplingDenoising,
+        Self::FrameGeneration,
+        Self::DeepDvc,
+        Self::DeepResolve,
+        Self::ImageSignalProcessing,
+        Self::ImageSuperResolution,
+        Self::InPainting,
+        Self::SlowMotion,
+        Self::VideoSuperResolution,
+    ];
+
+    /// The prefix of the feature's NGX capability parameters, e.g. `SuperSampling` for `SuperSampling.Available`.
+    pub fn parameter_prefix(&self) -> &'static str {
+        match self {
+            Self::SuperSampling => "SuperSampling",
+            Self::SuperSamplingDenoising => "SuperSamplingDenoising",
+            Self::FrameGeneration => "FrameGeneration",
+            Self::DeepDvc => "DeepDVC",
+            Self::DeepResolve => "DeepResolve",
+            Self::ImageSignalProcessing => "ImageSignalProcessing",
+            Self::ImageSuperResolution => "ImageSuperResolution",
+            Self::InPainting => "InPainting",
+            Self::SlowMotion => "SlowMotion",
+            Self::VideoSuperResolution => "VideoSuperResolution",
+        }
+    }
+}
+
+/// Availability of a DLSS feature, as reported by NGX.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DlssFeatureAvailability {
+    /// The feature is available on the current system.
+    pub available: bool,
+    /// The installed NVIDIA driver is too old to use the feature.
+    pub needs_updated_driver: bool,
+    /// The minimum driver version required by the feature, as `[major, minor]`, if reported.
+    pub min_driver_version: Option<[u32; 2]>,
+    /// The result of NGX initializing the feature, if reported. An error describes why the feature is unavailable.
+    ///
+    /// `None` if the driver reported a result code this crate does not know, see [`Self::init_result_code`].
+    pub init_result: Option<Result<(), DlssError>>,
+    /// The raw `NVSDK_NGX_Result` code of NGX initializing the feature, if reported.
+    pub init_result_code: Option<u32>,
+    /// The version of the feature's snippet, the library implementing it, if reported by the driver.
+    pub snippet_version: Option<u32>,
+}
+
 unsafe impl Send for DlssSdk {}
 unsafe impl Sync for DlssSdk {}
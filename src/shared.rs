@@ -0,0 +1,63 @@
+use crate::{
+    DlssError,
+    super_resolution::{DlssSuperResolution, DlssSuperResolutionRenderParameters},
+};
+use wgpu::{Adapter, CommandBuffer, CommandEncoder};
+
+/// Shares a single [`DlssSuperResolution`] context, and so its VRAM, between multiple views with identical settings.
+///
+/// `V` identifies a view, e.g. an editor preview viewport.
+///
+/// A context only holds the temporal history of one view at a time. Rendering a different view than the previous
+/// render forces [`DlssSuperResolutionRenderParameters::reset`], so views that alternate every frame get no temporal
+/// accumulation. This suits viewports that are rendered rarely or one at a time, not views that all update every frame.
+pub struct DlssSharedSuperResolution<V> {
+    context: DlssSuperResolution,
+    last_view: Option<V>,
+}
+
+impl<V: PartialEq> DlssSharedSuperResolution<V> {
+    /// Share `context` between views. Every view must use the context's upscaled and render resolutions.
+    pub fn new(context: DlssSuperResolution) -> Self {
+        Self {
+            context,
+            last_view: None,
+        }
+    }
+
+    /// Encode rendering commands for `view`, resetting the temporal history if the previous render was for a different view.
+    ///
+    /// See [`DlssSuperResolution::render`].
+    pub fn render(
+        &mut self,
+        view: V,
+        mut render_parameters: DlssSuperResolutionRenderParameters,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        render_parameters.reset |= self.last_view.as_ref() != Some(&view);
+        // Forget the view on failure, as the history may not belong to it anymore
+        self.last_view = None;
+
+        let command_buffer = self
+            .context
+            .render(render_parameters, command_encoder, adapter)?;
+        self.last_view = Some(view);
+        Ok(command_buffer)
+    }
+
+    /// The shared context. Use it to query the render resolution and suggested jitter.
+    pub fn context(&self) -> &DlssSuperResolution {
+        &self.context
+    }
+
+    /// The view the shared context currently holds the temporal history of, if any.
+    pub fn last_view(&self) -> Option<&V> {
+        self.last_view.as_ref()
+    }
+
+    /// Stop sharing, returning the context.
+    pub fn into_inner(self) -> DlssSuperResolution {
+        self.context
+    }
+}
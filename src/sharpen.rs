@@ -0,0 +1,111 @@
+use crate::mock::wgsl_storage_format;
+use std::{borrow::Cow, collections::HashMap};
+use wgpu::{
+    BindGroupDescriptor, BindGroupEntry, BindingResource, BufferUsages, CommandEncoder,
+    ComputePassDescriptor, ComputePipeline, ComputePipelineDescriptor, Device,
+    ShaderModuleDescriptor, ShaderSource, TextureFormat, TextureView,
+    util::{BufferInitDescriptor, DeviceExt},
+};
+
+/// Sharpens the DLSS output, replacing the sharpness setting DLSS deprecated.
+///
+/// Uses robust contrast adaptive sharpening (RCAS), which limits sharpening in high contrast areas to avoid halos.
+/// Expects color in the range `[0, 1]`, so HDR color should be sharpened after tonemapping.
+pub struct DlssSharpening {
+    device: Device,
+    pipelines: HashMap<TextureFormat, ComputePipeline>,
+}
+
+impl DlssSharpening {
+    /// Create a new [`DlssSharpening`]. Pipelines are created lazily for each target format.
+    pub fn new(device: &Device) -> Self {
+        Self {
+            device: device.clone(),
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Encode a compute pass into `command_encoder` that writes a sharpened copy of `source` to `target`,
+    /// e.g. after [`crate::super_resolution::DlssSuperResolution::render`].
+    ///
+    /// `sharpness` ranges from 0.0 (none) to 1.0 (maximum).
+    ///
+    /// `source` and `target` must be the same size, and different textures. `source` must have been created with
+    /// [`wgpu::TextureUsages::TEXTURE_BINDING`], and `target` with [`wgpu::TextureUsages::STORAGE_BINDING`].
+    ///
+    /// # Panics
+    /// Panics if the format of `target` is not one of
+    /// `Rgba8Unorm`, `Rgba16Float`, `Rgba32Float`, `Rgb10a2Unorm`, or `Rg11b10Ufloat`.
+    pub fn sharpen(
+        &mut self,
+        source: &TextureView,
+        target: &TextureView,
+        sharpness: f32,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        let target_format = target.texture().format();
+        let pipeline = self
+            .pipelines
+            .entry(target_format)
+            .or_insert_with(|| create_pipeline(&self.device, target_format));
+
+        let parameters = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("dlss_sharpening_parameters"),
+            contents: &sharpness.clamp(0.0, 1.0).to_ne_bytes(),
+            usage: BufferUsages::UNIFORM,
+        });
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("dlss_sharpening_bind_group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(source),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(target),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: parameters.as_entire_binding(),
+                },
+            ],
+        });
+
+        let target_size = target.texture().size();
+        let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("dlss_sharpening"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(
+            target_size.width.div_ceil(8),
+            target_size.height.div_ceil(8),
+            1,
+        );
+    }
+}
+
+fn create_pipeline(device: &Device, target_format: TextureFormat) -> ComputePipeline {
+    let target_format = wgsl_storage_format(target_format)
+        .unwrap_or_else(|| panic!("Unsupported sharpening target format {target_format:?}"));
+
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("dlss_sharpening_shader"),
+        source: ShaderSource::Wgsl(Cow::Owned(
+            include_str!("sharpen.wgsl").replace("OUTPUT_FORMAT", target_format),
+        )),
+    });
+
+    device.create_compute_pipeline(&ComputePipelineDescriptor {
+        label: Some("dlss_sharpening_pipeline"),
+        layout: None,
+        module: &shader,
+        entry_point: Some("sharpen"),
+        compilation_options: Default::default(),
+        cache: None,
+    })
+}
@@ -0,0 +1,38 @@
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var output: texture_storage_2d<OUTPUT_FORMAT, write>;
+@group(0) @binding(2) var<uniform> sharpness: f32;
+
+// Maximum negative lobe weight, from FidelityFX RCAS
+const LIMIT: f32 = 0.25 - 1.0 / 16.0;
+
+fn load(coordinates: vec2<i32>) -> vec3<f32> {
+    let max_coordinates = vec2<i32>(textureDimensions(source)) - 1;
+    return textureLoad(source, clamp(coordinates, vec2<i32>(0), max_coordinates), 0).rgb;
+}
+
+// Robust contrast adaptive sharpening: a cross-shaped filter whose negative lobe is limited so that
+// the result stays within the range of the neighbourhood
+@compute @workgroup_size(8, 8, 1)
+fn sharpen(@builtin(global_invocation_id) id: vec3<u32>) {
+    let output_size = textureDimensions(output);
+    if any(id.xy >= output_size) {
+        return;
+    }
+
+    let coordinates = vec2<i32>(id.xy);
+    let b = load(coordinates + vec2<i32>(0, -1));
+    let d = load(coordinates + vec2<i32>(-1, 0));
+    let e = textureLoad(source, coordinates, 0);
+    let f = load(coordinates + vec2<i32>(1, 0));
+    let h = load(coordinates + vec2<i32>(0, 1));
+
+    let minimum = min(min(b, d), min(f, h));
+    let maximum = max(max(b, d), max(f, h));
+    let hit_min = minimum / max(4.0 * maximum, vec3<f32>(1e-5));
+    let hit_max = (1.0 - maximum) / min(4.0 * minimum - 4.0, vec3<f32>(-1e-5));
+    let lobe_rgb = max(-hit_min, hit_max);
+    let lobe = max(-LIMIT, min(max(lobe_rgb.r, max(lobe_rgb.g, lobe_rgb.b)), 0.0)) * sharpness;
+
+    let rgb = (lobe * (b + d + f + h) + e.rgb) / (4.0 * lobe + 1.0);
+    textureStore(output, id.xy, vec4<f32>(rgb, e.a));
+}
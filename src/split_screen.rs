@@ -0,0 +1,100 @@
+use crate::{
+    DlssError, DlssFeatureFlags, DlssPerfQualityMode, DlssSdk,
+    super_resolution::{DlssSuperResolution, DlssSuperResolutionRenderParameters},
+};
+use std::sync::{Arc, Mutex};
+use wgpu::{Adapter, CommandBuffer, CommandEncoder, Device, Queue};
+
+/// Manages one [`DlssSuperResolution`] context per viewport, each upscaling into its own subrect of a shared output texture.
+///
+/// Useful for split-screen rendering.
+pub struct DlssSplitScreen {
+    viewports: Vec<DlssViewport>,
+}
+
+/// A single viewport of a [`DlssSplitScreen`].
+pub struct DlssViewport {
+    /// Origin of the viewport within the shared output texture.
+    pub output_origin: [u32; 2],
+    /// The DLSS context for this viewport. Use it to query the render resolution and suggested jitter.
+    pub context: DlssSuperResolution,
+}
+
+impl DlssSplitScreen {
+    /// Create a context for each viewport, given as `(output_origin, output_size)` within the shared output texture.
+    ///
+    /// [`DlssFeatureFlags::OutputSubrect`] is always enabled.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn new(
+        viewports: &[([u32; 2], [u32; 2])],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        sdk: Arc<Mutex<DlssSdk>>,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Self, DlssError> {
+        let viewports = viewports
+            .iter()
+            .map(|&(output_origin, output_size)| {
+                Ok(DlssViewport {
+                    output_origin,
+                    context: DlssSuperResolution::new(
+                        output_size,
+                        perf_quality_mode,
+                        feature_flags | DlssFeatureFlags::OutputSubrect,
+                        Arc::clone(&sdk),
+                        device,
+                        queue,
+                    )?,
+                })
+            })
+            .collect::<Result<_, DlssError>>()?;
+
+        Ok(Self { viewports })
+    }
+
+    /// The viewports, in the order they were provided to [`Self::new`].
+    pub fn viewports(&self) -> &[DlssViewport] {
+        &self.viewports
+    }
+
+    /// Encode rendering commands for every viewport, with one set of render parameters per viewport, in order.
+    ///
+    /// [`DlssSuperResolutionRenderParameters::output_subrect_base`] is overridden with each viewport's origin,
+    /// and all viewports should use the same [`DlssSuperResolutionRenderParameters::dlss_output`].
+    ///
+    /// The resulting command buffers should be submitted in order immediately after the finished `command_encoder`,
+    /// following the same rules as [`DlssSuperResolution::render`].
+    /// ```compile_fail
+    /// let dlss_command_buffers = split_screen.render(render_parameters, &mut my_command_encoder, adapter).unwrap();
+    /// queue.submit(iter::once(my_command_encoder.finish()).chain(dlss_command_buffers));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the number of render parameters does not match the number of viewports.
+    pub fn render<'a>(
+        &mut self,
+        render_parameters: impl IntoIterator<Item = DlssSuperResolutionRenderParameters<'a>>,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<Vec<CommandBuffer>, DlssError> {
+        let render_parameters = render_parameters.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            render_parameters.len(),
+            self.viewports.len(),
+            "Expected one set of render parameters per viewport"
+        );
+
+        self.viewports
+            .iter_mut()
+            .zip(render_parameters)
+            .map(|(viewport, mut render_parameters)| {
+                render_parameters.output_subrect_base = Some(viewport.output_origin);
+                viewport
+                    .context
+                    .render(render_parameters, command_encoder, adapter)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,75 @@
+use crate::DlssPerfQualityMode;
+
+/// Hashes `values` with FNV-1a, as little-endian bytes, so that the result is the same on every platform and across runs.
+///
+/// Callers convert their fields to `u64`s explicitly, rather than relying on [`std::hash::Hash`] impls,
+/// whose output for enum discriminants and slices is not guaranteed to be the same across Rust versions and platforms.
+pub(crate) fn stable_hash(values: &[u64]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325;
+    for byte in values.iter().flat_map(|value| value.to_le_bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Identifies `perf_quality_mode` for [`stable_hash`].
+pub(crate) fn perf_quality_mode_key(perf_quality_mode: DlssPerfQualityMode) -> u64 {
+    match perf_quality_mode {
+        DlssPerfQualityMode::Auto => 0,
+        DlssPerfQualityMode::Dlaa => 1,
+        DlssPerfQualityMode::Quality => 2,
+        DlssPerfQualityMode::Balanced => 3,
+        DlssPerfQualityMode::Performance => 4,
+        DlssPerfQualityMode::UltraPerformance => 5,
+        DlssPerfQualityMode::Custom(ratio) => 6 << 32 | ratio.to_bits() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DlssFeatureFlags,
+        ray_reconstruction::{
+            DlssRayReconstructionDepthMode, DlssRayReconstructionRoughnessMode,
+            DlssRayReconstructionSettings,
+        },
+        super_resolution::DlssSuperResolutionSettings,
+    };
+
+    #[test]
+    fn fnv1a() {
+        assert_eq!(stable_hash(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(&[0]), 0xa8c7_f832_281a_39c5);
+    }
+
+    // These values must not change, as callers may persist them
+    #[test]
+    fn super_resolution_settings_hash() {
+        let settings = DlssSuperResolutionSettings {
+            upscaled_resolution: [3840, 2160],
+            perf_quality_mode: DlssPerfQualityMode::Quality,
+            feature_flags: DlssFeatureFlags::HighDynamicRange | DlssFeatureFlags::AutoExposure,
+        };
+        assert_eq!(settings.settings_hash(), 0xd7f2_0b08_f5f1_de3b);
+
+        let settings = DlssSuperResolutionSettings {
+            perf_quality_mode: DlssPerfQualityMode::Custom(0.75),
+            ..settings
+        };
+        assert_eq!(settings.settings_hash(), 0xdb29_1a90_3754_0e22);
+    }
+
+    #[test]
+    fn ray_reconstruction_settings_hash() {
+        let settings = DlssRayReconstructionSettings {
+            upscaled_resolution: [1920, 1080],
+            perf_quality_mode: DlssPerfQualityMode::Performance,
+            feature_flags: DlssFeatureFlags::HighDynamicRange,
+            roughness_mode: DlssRayReconstructionRoughnessMode::Packed,
+            depth_mode: DlssRayReconstructionDepthMode::Hardware,
+        };
+        assert_eq!(settings.settings_hash(), 0xc085_aa60_398b_0819);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    DlssError, DlssFeatureFlags, DlssPerfQualityMode, DlssSdk, DlssTemporalUpscaler,
+    super_resolution::DlssSuperResolution,
+};
+use std::sync::{Arc, Mutex};
+use wgpu::{Adapter, CommandBuffer, CommandEncoder, Device, Queue};
+
+/// Pairs two contexts with identical settings, one per eye, for VR rendering.
+///
+/// Both eyes should be rendered with the jitter from [`Self::suggested_jitter`], so that their jitter sequences stay in sync,
+/// and with the same `reset` value.
+///
+/// For eyes stored as layers of a single texture array, see [`crate::DlssLayeredSuperResolution`].
+pub struct DlssStereo<U> {
+    eyes: [U; 2],
+}
+
+impl DlssStereo<DlssSuperResolution> {
+    /// Create a [`DlssSuperResolution`] context for each eye.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn super_resolution(
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        sdk: Arc<Mutex<DlssSdk>>,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Self, DlssError> {
+        let create = || {
+            DlssSuperResolution::new(
+                upscaled_resolution,
+                perf_quality_mode,
+                feature_flags,
+                Arc::clone(&sdk),
+                device,
+                queue,
+            )
+        };
+        Ok(Self::new(create()?, create()?))
+    }
+}
+
+impl<U: DlssTemporalUpscaler> DlssStereo<U> {
+    /// Pair a context for the left eye with one for the right eye.
+    ///
+    /// # Panics
+    /// Panics if the contexts have different upscaled or render resolutions.
+    pub fn new(left: U, right: U) -> Self {
+        assert_eq!(
+            left.upscaled_resolution(),
+            right.upscaled_resolution(),
+            "Expected both eyes to have the same upscaled resolution"
+        );
+        assert_eq!(
+            left.render_resolution(),
+            right.render_resolution(),
+            "Expected both eyes to have the same render resolution"
+        );
+
+        Self {
+            eyes: [left, right],
+        }
+    }
+
+    /// Encode rendering commands for both eyes, left first.
+    ///
+    /// The resulting command buffers should be submitted in order immediately after the finished `command_encoder`,
+    /// following the same rules as [`DlssSuperResolution::render`].
+    pub fn render_eyes(
+        &mut self,
+        [left, right]: [U::RenderParameters<'_>; 2],
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<[CommandBuffer; 2], DlssError> {
+        let [left_eye, right_eye] = &mut self.eyes;
+        Ok([
+            left_eye.render(left, command_encoder, adapter)?,
+            right_eye.render(right, command_encoder, adapter)?,
+        ])
+    }
+
+    /// Suggested subpixel camera jitter for a given frame, shared by both eyes.
+    ///
+    /// See [`DlssSuperResolution::suggested_jitter`].
+    pub fn suggested_jitter(&self, frame_number: u32) -> [f32; 2] {
+        self.eyes[0].suggested_jitter(frame_number, self.render_resolution())
+    }
+
+    /// Suggested mip bias to apply when sampling textures, shared by both eyes.
+    pub fn suggested_mip_bias(&self) -> f32 {
+        self.eyes[0].suggested_mip_bias(self.render_resolution())
+    }
+
+    /// The upscaled resolution of each eye.
+    pub fn upscaled_resolution(&self) -> [u32; 2] {
+        self.eyes[0].upscaled_resolution()
+    }
+
+    /// The resolution each eye should render at, pre-upscaling.
+    pub fn render_resolution(&self) -> [u32; 2] {
+        self.eyes[0].render_resolution()
+    }
+
+    /// The left and right eye contexts.
+    pub fn eyes(&self) -> &[U; 2] {
+        &self.eyes
+    }
+
+    /// Stop pairing, returning the left and right eye contexts.
+    pub fn into_inner(self) -> [U; 2] {
+        self.eyes
+    }
+}
@@ -1,23 +1,50 @@
-use crate::{DlssSdk, nvsdk_ngx::*};
+use crate::{
+    DlssArrayLayers, DlssCounters, DlssCreationTiming, DlssGpuTimeStatistics, DlssPassDescriptor,
+    DlssPassRole, DlssPerfQualityMode, DlssSdk, DlssSuperResolutionPreset,
+    counters::CreationTimer,
+    gpu_timer::GpuTimer,
+    jitter::{jitter_offset, jitter_phase_count, mip_bias},
+    nvsdk_ngx::*,
+    raw::VulkanTexture,
+    stable_hash::{perf_quality_mode_key, stable_hash},
+};
 use std::{
     iter,
     ops::RangeInclusive,
     ptr,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::Instant,
 };
 use wgpu::{
     Adapter, CommandBuffer, CommandEncoder, CommandEncoderDescriptor, Device, Queue, Texture,
-    TextureTransition, TextureUses, TextureView, hal::api::Vulkan,
+    TextureTransition, TextureView,
+    hal::{CommandEncoder as _, api::Vulkan},
 };
 
 /// Camera-specific object for using DLSS Super Resolution.
+///
+/// Holds a strong reference to the [`Device`] and the [`DlssSdk`], keeping both alive until this context is dropped.
 pub struct DlssSuperResolution {
     upscaled_resolution: [u32; 2],
+    optimal_render_resolution: [u32; 2],
     min_render_resolution: [u32; 2],
     max_render_resolution: [u32; 2],
+    feature_flags: DlssFeatureFlags,
     device: Device,
     sdk: Arc<Mutex<DlssSdk>>,
+    sdk_generation: u64,
     feature: *mut NVSDK_NGX_Handle,
+    /// Boxed to keep the context cheap to move.
+    eval_params: Box<NVSDK_NGX_VK_DLSS_Eval_Params>,
+    pub(crate) counters: DlssCounters,
+    creation_timer: CreationTimer,
+    gpu_timer: Option<GpuTimer>,
+    pending_reset: bool,
+    jitter_phase_offset: u32,
+    preset: DlssSuperResolutionPreset,
+    /// Set once the GPU is known to have finished all work using this context, so that dropping it does not wait for the device to be idle.
+    pub(crate) gpu_work_done: bool,
 }
 
 impl DlssSuperResolution {
@@ -34,7 +61,42 @@ impl DlssSuperResolution {
         device: &Device,
         queue: &Queue,
     ) -> Result<Self, DlssError> {
-        let locked_sdk = sdk.lock().unwrap();
+        let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("dlss_super_resolution_context_creation"),
+        });
+
+        let dlss = unsafe {
+            command_encoder.as_hal_mut::<Vulkan, _, _>(|command_encoder| {
+                Self::new_raw(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    sdk,
+                    command_encoder.unwrap().raw_handle(),
+                )
+            })?
+        };
+
+        queue.submit([command_encoder.finish()]);
+        dlss.creation_timer.time_gpu(queue);
+
+        Ok(dlss)
+    }
+
+    /// Like [`Self::new`], but records context creation commands into a raw Vulkan command buffer.
+    ///
+    /// # Safety
+    /// `command_buffer` must be in the recording state, and must have been allocated from the same `VkDevice` as `sdk`.
+    /// It must be submitted before calling [`Self::render`] or [`Self::render_raw`].
+    pub unsafe fn new_raw(
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        sdk: Arc<Mutex<DlssSdk>>,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<Self, DlssError> {
+        let locked_sdk = sdk.lock().map_err(|_| DlssError::SdkPoisoned)?;
+        let creation_start = Instant::now();
 
         let perf_quality_value = perf_quality_mode.as_perf_quality_value(upscaled_resolution);
 
@@ -57,11 +119,18 @@ impl DlssSuperResolution {
                 &mut deprecated_sharpness,
             ))?;
         }
-        if perf_quality_mode == DlssPerfQualityMode::Dlaa {
+        if perf_quality_mode.resolve(upscaled_resolution) == DlssPerfQualityMode::Dlaa {
             optimal_render_resolution = upscaled_resolution;
             min_render_resolution = upscaled_resolution;
             max_render_resolution = upscaled_resolution;
         }
+        if let Some(custom_render_resolution) = perf_quality_mode.custom_render_resolution(
+            upscaled_resolution,
+            min_render_resolution,
+            max_render_resolution,
+        ) {
+            optimal_render_resolution = custom_render_resolution;
+        }
 
         let mut create_params = NVSDK_NGX_DLSS_Create_Params {
             Feature: NVSDK_NGX_Feature_Create_Params {
@@ -75,33 +144,41 @@ impl DlssSuperResolution {
             InEnableOutputSubrects: feature_flags.contains(DlssFeatureFlags::OutputSubrect),
         };
 
-        let mut command_encoder = device.create_command_encoder(&CommandEncoderDescriptor {
-            label: Some("dlss_super_resolution_context_creation"),
-        });
-
         let mut feature = ptr::null_mut();
         unsafe {
-            command_encoder.as_hal_mut::<Vulkan, _, _>(|command_encoder| {
-                check_ngx_result(NGX_VULKAN_CREATE_DLSS_EXT(
-                    command_encoder.unwrap().raw_handle(),
-                    1,
-                    1,
-                    &mut feature,
-                    locked_sdk.parameters,
-                    &mut create_params,
-                ))
-            })?
+            locked_sdk
+                .super_resolution_presets
+                .apply(locked_sdk.parameters);
+            check_ngx_result(NGX_VULKAN_CREATE_DLSS_EXT(
+                command_buffer,
+                1,
+                1,
+                &mut feature,
+                locked_sdk.parameters,
+                &mut create_params,
+            ))?;
         }
 
-        queue.submit([command_encoder.finish()]);
-
         Ok(Self {
             upscaled_resolution,
+            optimal_render_resolution,
             min_render_resolution,
             max_render_resolution,
-            device: device.clone(),
+            feature_flags,
+            device: locked_sdk.device.clone(),
             sdk: Arc::clone(&sdk),
+            sdk_generation: locked_sdk.generation,
             feature,
+            eval_params: Box::new(eval_params_template()),
+            counters: DlssCounters::default(),
+            creation_timer: CreationTimer::new(creation_start.elapsed()),
+            gpu_timer: None,
+            pending_reset: true,
+            jitter_phase_offset: 0,
+            preset: locked_sdk
+                .super_resolution_presets
+                .get(perf_quality_mode, upscaled_resolution),
+            gpu_work_done: false,
         })
     }
 
@@ -121,76 +198,84 @@ impl DlssSuperResolution {
         command_encoder: &mut CommandEncoder,
         adapter: &Adapter,
     ) -> Result<CommandBuffer, DlssError> {
-        render_parameters.validate()?;
+        self.render_array_layers(
+            render_parameters,
+            |_| DlssArrayLayers::default(),
+            command_encoder,
+            adapter,
+        )
+    }
 
-        let sdk = self.sdk.lock().unwrap();
+    /// Like [`Self::render`], but for texture views of specific array layers, such as one eye of an OpenXR array swapchain.
+    ///
+    /// `array_layers` returns the layers covered by the texture view passed for each input and the output.
+    pub fn render_array_layers(
+        &mut self,
+        mut render_parameters: DlssSuperResolutionRenderParameters,
+        array_layers: impl Fn(DlssPassRole) -> DlssArrayLayers,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        self.prepare(&mut render_parameters, adapter)?;
 
-        let partial_texture_size = render_parameters
-            .partial_texture_size
-            .unwrap_or(self.max_render_resolution);
+        command_encoder.transition_resources(iter::empty(), render_parameters.barrier_list());
 
-        let (exposure, exposure_scale, pre_exposure) = match &render_parameters.exposure {
-            DlssSuperResolutionExposure::Manual {
-                exposure,
-                exposure_scale,
-                pre_exposure,
-            } => (
-                &mut texture_to_ngx(exposure, adapter) as *mut _,
-                exposure_scale.unwrap_or(1.0),
-                pre_exposure.unwrap_or(0.0),
-            ),
-            DlssSuperResolutionExposure::Automatic => (ptr::null_mut(), 0.0, 0.0),
-        };
+        // DLSS commands are recorded via wgpu-hal, and so do not show up in wgpu API traces
+        command_encoder.insert_debug_marker(&format!(
+            "dlss_super_resolution upscaled_resolution={:?} render_resolution={:?} reset={}",
+            self.upscaled_resolution,
+            render_parameters
+                .partial_texture_size
+                .unwrap_or(self.optimal_render_resolution),
+            render_parameters.reset,
+        ));
 
-        let mut eval_params = NVSDK_NGX_VK_DLSS_Eval_Params {
-            Feature: NVSDK_NGX_VK_Feature_Eval_Params {
-                pInColor: &mut texture_to_ngx(render_parameters.color, adapter) as *mut _,
-                pInOutput: &mut texture_to_ngx(render_parameters.dlss_output, adapter) as *mut _,
-                InSharpness: 0.0,
-            },
-            pInDepth: &mut texture_to_ngx(render_parameters.depth, adapter) as *mut _,
-            pInMotionVectors: &mut texture_to_ngx(render_parameters.motion_vectors, adapter)
-                as *mut _,
-            InJitterOffsetX: render_parameters.jitter_offset[0],
-            InJitterOffsetY: render_parameters.jitter_offset[1],
-            InRenderSubrectDimensions: NVSDK_NGX_Dimensions {
-                Width: partial_texture_size[0],
-                Height: partial_texture_size[1],
-            },
-            InReset: render_parameters.reset as _,
-            InMVScaleX: render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[0],
-            InMVScaleY: render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[1],
-            pInTransparencyMask: ptr::null_mut(),
-            pInExposureTexture: exposure,
-            pInBiasCurrentColorMask: match &render_parameters.bias {
-                Some(bias) => &mut texture_to_ngx(bias, adapter) as *mut _,
-                None => ptr::null_mut(),
-            },
-            InColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InDepthSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InMVSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InTranslucencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InBiasCurrentColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InOutputSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
-            InPreExposure: pre_exposure,
-            InExposureScale: exposure_scale,
-            InIndicatorInvertXAxis: 0,
-            InIndicatorInvertYAxis: 0,
-            GBufferSurface: NVSDK_NGX_VK_GBuffer {
-                pInAttrib: [ptr::null_mut(); 16],
-            },
-            InToneMapperType: NVSDK_NGX_ToneMapperType_NVSDK_NGX_TONEMAPPER_STRING,
-            pInMotionVectors3D: ptr::null_mut(),
-            pInIsParticleMask: ptr::null_mut(),
-            pInAnimatedTextureMask: ptr::null_mut(),
-            pInDepthHighRes: ptr::null_mut(),
-            pInPositionViewSpace: ptr::null_mut(),
-            InFrameTimeDeltaInMsec: 0.0,
-            pInRayTracingHitDistance: ptr::null_mut(),
-            pInMotionVectorsReflections: ptr::null_mut(),
-        };
+        self.record(&render_parameters, array_layers, adapter)
+    }
 
-        command_encoder.transition_resources(iter::empty(), render_parameters.barrier_list());
+    /// Like [`Self::render`], but the transitions from [`DlssSuperResolutionRenderParameters::barrier_list`]
+    /// must already have been recorded by the caller.
+    pub(crate) fn render_without_transitions(
+        &mut self,
+        mut render_parameters: DlssSuperResolutionRenderParameters,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        self.prepare(&mut render_parameters, adapter)?;
+        self.record(&render_parameters, |_| DlssArrayLayers::default(), adapter)
+    }
+
+    fn prepare(
+        &mut self,
+        render_parameters: &mut DlssSuperResolutionRenderParameters,
+        adapter: &Adapter,
+    ) -> Result<(), DlssError> {
+        if let Err(err) = check_adapter(&self.device, adapter).and_then(|()| {
+            render_parameters.validate(
+                self.feature_flags,
+                self.upscaled_resolution,
+                |texture_view| texture_view.texture().format().is_srgb(),
+                |texture_view| {
+                    let size = texture_view.texture().size();
+                    [size.width, size.height]
+                },
+            )
+        }) {
+            self.counters.validation_failures += 1;
+            return Err(err);
+        }
+        render_parameters.reset |= self.pending_reset;
+        Ok(())
+    }
+
+    fn record(
+        &mut self,
+        render_parameters: &DlssSuperResolutionRenderParameters,
+        array_layers: impl Fn(DlssPassRole) -> DlssArrayLayers,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        let mut resources = render_parameters.ngx_resources(|role, texture_view| {
+            texture_to_ngx(texture_view, array_layers(role), adapter)
+        });
 
         let mut dlss_command_encoder =
             self.device
@@ -199,29 +284,240 @@ impl DlssSuperResolution {
                 });
         unsafe {
             dlss_command_encoder.as_hal_mut::<Vulkan, _, _>(|command_encoder| {
-                check_ngx_result(NGX_VULKAN_EVALUATE_DLSS_EXT(
-                    command_encoder.unwrap().raw_handle(),
-                    self.feature,
-                    sdk.parameters,
-                    &mut eval_params,
-                ))
+                let command_encoder = command_encoder.unwrap();
+                command_encoder.begin_debug_marker("dlss_super_resolution");
+                let result = self.evaluate_timed(
+                    render_parameters,
+                    &mut resources,
+                    command_encoder.raw_handle(),
+                );
+                command_encoder.end_debug_marker();
+                result
             })?;
         }
+        self.counters.record_evaluation(render_parameters.reset);
+        self.pending_reset = false;
+
         Ok(dlss_command_encoder.finish())
     }
 
+    /// Like [`Self::render`], but uses raw Vulkan textures and records into a raw Vulkan command buffer.
+    ///
+    /// # Safety
+    /// `command_buffer` must be in the recording state, and must have been allocated from the same `VkDevice` as the [`DlssSdk`].
+    ///
+    /// `command_buffer` may be a secondary command buffer, as long as it was not begun with `VK_COMMAND_BUFFER_USAGE_RENDER_PASS_CONTINUE_BIT`.
+    /// It must then be executed from a primary command buffer outside of a render pass.
+    ///
+    /// Input textures must be in `VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL`, and the output texture in `VK_IMAGE_LAYOUT_GENERAL`.
+    ///
+    /// DLSS will modify the command buffer's bound pipeline, descriptor sets, and push constants.
+    pub unsafe fn render_raw(
+        &mut self,
+        mut render_parameters: DlssSuperResolutionRenderParameters<VulkanTexture>,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<(), DlssError> {
+        if let Err(err) = render_parameters.validate(
+            self.feature_flags,
+            self.upscaled_resolution,
+            VulkanTexture::is_srgb,
+            VulkanTexture::size,
+        ) {
+            self.counters.validation_failures += 1;
+            return Err(err);
+        }
+        render_parameters.reset |= self.pending_reset;
+
+        let mut resources = render_parameters.ngx_resources(|_, texture| texture.as_ngx());
+
+        unsafe { self.evaluate_timed(&render_parameters, &mut resources, command_buffer)? };
+        self.counters.record_evaluation(render_parameters.reset);
+        self.pending_reset = false;
+
+        Ok(())
+    }
+
+    unsafe fn evaluate_timed<T>(
+        &mut self,
+        render_parameters: &DlssSuperResolutionRenderParameters<T>,
+        resources: &mut DlssSuperResolutionResources,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<(), DlssError> {
+        let mut gpu_timer = self.gpu_timer.take();
+        let result = unsafe {
+            match &mut gpu_timer {
+                Some(gpu_timer) => gpu_timer.time(command_buffer, || {
+                    self.evaluate(render_parameters, resources, command_buffer)
+                }),
+                None => self.evaluate(render_parameters, resources, command_buffer),
+            }
+        };
+        self.gpu_timer = gpu_timer;
+        result
+    }
+
+    unsafe fn evaluate<T>(
+        &mut self,
+        render_parameters: &DlssSuperResolutionRenderParameters<T>,
+        resources: &mut DlssSuperResolutionResources,
+        command_buffer: ash::vk::CommandBuffer,
+    ) -> Result<(), DlssError> {
+        let sdk = self.sdk.lock().map_err(|_| DlssError::SdkPoisoned)?;
+        if sdk.generation != self.sdk_generation {
+            return Err(DlssError::SdkReinitialized);
+        }
+
+        let partial_texture_size = render_parameters
+            .partial_texture_size
+            .unwrap_or(self.optimal_render_resolution);
+        let output_subrect_base = render_parameters.output_subrect_base.unwrap_or([0, 0]);
+
+        let (exposure_scale, pre_exposure) = match &render_parameters.exposure {
+            DlssSuperResolutionExposure::Manual {
+                exposure_scale,
+                pre_exposure,
+                ..
+            } => (exposure_scale.unwrap_or(1.0), pre_exposure.unwrap_or(0.0)),
+            DlssSuperResolutionExposure::Automatic => (0.0, 0.0),
+        };
+
+        // Every resource pointer is overwritten each frame, so pointers to a previous frame's resources are never read
+        let eval_params = &mut *self.eval_params;
+        eval_params.Feature.pInColor = &mut resources.color;
+        eval_params.Feature.pInOutput = &mut resources.dlss_output;
+        eval_params.pInDepth = &mut resources.depth;
+        eval_params.pInMotionVectors = &mut resources.motion_vectors;
+        eval_params.InJitterOffsetX = render_parameters.jitter_offset[0];
+        eval_params.InJitterOffsetY = render_parameters.jitter_offset[1];
+        eval_params.InRenderSubrectDimensions = NVSDK_NGX_Dimensions {
+            Width: partial_texture_size[0],
+            Height: partial_texture_size[1],
+        };
+        eval_params.InReset = render_parameters.reset as _;
+        eval_params.InMVScaleX = render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[0];
+        eval_params.InMVScaleY = render_parameters.motion_vector_scale.unwrap_or([1.0, 1.0])[1];
+        eval_params.pInExposureTexture = optional_resource(&mut resources.exposure);
+        eval_params.pInBiasCurrentColorMask = optional_resource(&mut resources.bias);
+        eval_params.InOutputSubrectBase = NVSDK_NGX_Coordinates {
+            X: output_subrect_base[0],
+            Y: output_subrect_base[1],
+        };
+        eval_params.InPreExposure = pre_exposure;
+        eval_params.InExposureScale = exposure_scale;
+
+        unsafe {
+            check_ngx_result(NGX_VULKAN_EVALUATE_DLSS_EXT(
+                command_buffer,
+                self.feature,
+                sdk.parameters,
+                eval_params,
+            ))
+        }
+    }
+
     /// Suggested subpixel camera jitter for a given frame.
     pub fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
-        let ratio = self.upscaled_resolution[0] as f32 / render_resolution[0] as f32;
-        let phase_count = (8.0 * ratio * ratio) as u32;
-        let i = frame_number % phase_count;
+        jitter_offset(
+            frame_number.wrapping_add(self.jitter_phase_offset),
+            self.suggested_jitter_phase_count(render_resolution),
+        )
+    }
 
-        [halton_sequence(i, 2) - 0.5, halton_sequence(i, 3) - 0.5]
+    /// Offset the sequence returned by [`Self::suggested_jitter`] by `offset` phases.
+    ///
+    /// Give simultaneous cameras, such as stereo eyes, different offsets so that they do not sample identical jitter each frame.
+    /// The offset must be set again after recreating the context.
+    pub fn set_jitter_phase_offset(&mut self, offset: u32) {
+        self.jitter_phase_offset = offset;
+    }
+
+    /// The offset set by [`Self::set_jitter_phase_offset`], 0 by default.
+    pub fn jitter_phase_offset(&self) -> u32 {
+        self.jitter_phase_offset
+    }
+
+    /// Suggested number of jitter phases to cycle through before repeating, used by [`Self::suggested_jitter`].
+    pub fn suggested_jitter_phase_count(&self, render_resolution: [u32; 2]) -> u32 {
+        jitter_phase_count(render_resolution, self.upscaled_resolution)
     }
 
     /// Suggested mip bias to apply when sampling textures.
     pub fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
-        (render_resolution[0] as f32 / self.upscaled_resolution[0] as f32).log2() - 1.0
+        mip_bias(render_resolution, self.upscaled_resolution)
+    }
+
+    /// Start measuring the GPU time of each evaluation, for [`Self::gpu_time_statistics`].
+    ///
+    /// Timing is measured using Vulkan timestamp queries, and requires `queue` to support them.
+    /// An evaluation's timing is available once the next evaluation has been rendered and `queue` has finished the former.
+    pub fn enable_gpu_timing(&mut self, queue: &Queue) -> Result<(), DlssError> {
+        if self.gpu_timer.is_none() {
+            self.gpu_timer =
+                Some(GpuTimer::new(&self.device, queue).map_err(|_| DlssError::PlatformError)?);
+        }
+        Ok(())
+    }
+
+    /// Rolling statistics of the GPU time taken by recent evaluations.
+    ///
+    /// Returns `None` if [`Self::enable_gpu_timing`] has not been called, or no evaluations have completed on the GPU yet.
+    pub fn gpu_time_statistics(&mut self) -> Option<DlssGpuTimeStatistics> {
+        let gpu_timer = self.gpu_timer.as_mut()?;
+        gpu_timer.poll();
+        gpu_timer.statistics()
+    }
+
+    /// Whether the next evaluation will reset temporal history regardless of the `reset` render parameter.
+    ///
+    /// This is true for newly created contexts, including those recreated after a settings or resolution change,
+    /// and after calling [`Self::request_reset`].
+    pub fn pending_reset(&self) -> bool {
+        self.pending_reset
+    }
+
+    /// Reset temporal history on the next evaluation.
+    pub fn request_reset(&mut self) {
+        self.pending_reset = true;
+    }
+
+    /// The underlying NGX feature handle, for calling NGX functions this crate does not wrap.
+    ///
+    /// Use [`DlssSdk::raw_parameters`] for the NGX parameters this context was created with.
+    ///
+    /// # Safety
+    /// The handle must not be released, and must only be used while the [`DlssSdk`] mutex is locked.
+    pub unsafe fn raw_feature_handle(&self) -> *mut NVSDK_NGX_Handle {
+        self.feature
+    }
+
+    /// Usage counters for this context.
+    pub fn counters(&self) -> DlssCounters {
+        self.counters
+    }
+
+    /// How long creating this context took.
+    pub fn creation_timing(&self) -> DlssCreationTiming {
+        self.creation_timer.timing()
+    }
+
+    /// The [`Device`] this context was created on, which it keeps alive.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The [`DlssSdk`] this context was created from, which it keeps alive.
+    pub fn sdk(&self) -> &Arc<Mutex<DlssSdk>> {
+        &self.sdk
+    }
+
+    /// The feature flags this context was created with.
+    pub fn feature_flags(&self) -> DlssFeatureFlags {
+        self.feature_flags
+    }
+
+    /// The preset this context was created with, see [`DlssSdk::set_super_resolution_presets`].
+    pub fn preset(&self) -> DlssSuperResolutionPreset {
+        self.preset
     }
 
     /// The upscaled resolution DLSS will output at.
@@ -230,27 +526,66 @@ impl DlssSuperResolution {
     }
 
     /// The resolution the camera should render at, pre-upscaling.
+    ///
+    /// This is the same as [`Self::optimal_render_resolution`].
     pub fn render_resolution(&self) -> [u32; 2] {
+        self.optimal_render_resolution
+    }
+
+    /// The render resolution NGX recommends for the selected [`DlssPerfQualityMode`].
+    pub fn optimal_render_resolution(&self) -> [u32; 2] {
+        self.optimal_render_resolution
+    }
+
+    /// The smallest render resolution DLSS supports for this context.
+    pub fn min_render_resolution(&self) -> [u32; 2] {
         self.min_render_resolution
     }
 
+    /// The largest render resolution DLSS supports for this context.
+    pub fn max_render_resolution(&self) -> [u32; 2] {
+        self.max_render_resolution
+    }
+
     /// Like [`Self::render_resolution`], but returns a range of values for use with dynamic resolution scaling.
     pub fn render_resolution_range(&self) -> RangeInclusive<[u32; 2]> {
         self.min_render_resolution..=self.max_render_resolution
     }
+
+    /// The range of upscale factors (upscaled width divided by render width) DLSS supports for this context,
+    /// derived from [`Self::render_resolution_range`]. Useful for clamping resolution scale settings.
+    pub fn upscale_factor_range(&self) -> RangeInclusive<f32> {
+        let upscale_factor = |render_resolution: [u32; 2]| {
+            self.upscaled_resolution[0] as f32 / render_resolution[0] as f32
+        };
+        upscale_factor(self.max_render_resolution)..=upscale_factor(self.min_render_resolution)
+    }
 }
 
 impl Drop for DlssSuperResolution {
     fn drop(&mut self) {
+        // Serialize with other NGX calls, even if the lock was poisoned by a panic
+        let sdk = self.sdk.lock().unwrap_or_else(PoisonError::into_inner);
+        // The feature was already released if the SDK was reinitialized
+        if sdk.generation != self.sdk_generation {
+            return;
+        }
+
         unsafe {
             let hal_device = self.device.as_hal::<Vulkan>().unwrap();
-            hal_device
-                .raw_device()
-                .device_wait_idle()
-                .expect("Failed to wait for idle device when destroying DlssSuperResolution");
+            let wait_result = if self.gpu_work_done {
+                Ok(())
+            } else {
+                hal_device.raw_device().device_wait_idle()
+            };
+            let release_result = check_ngx_result(NVSDK_NGX_VULKAN_ReleaseFeature(self.feature));
 
-            check_ngx_result(NVSDK_NGX_VULKAN_ReleaseFeature(self.feature))
-                .expect("Failed to destroy DlssSuperResolution feature");
+            // Panicking while already unwinding would abort
+            if !thread::panicking() {
+                wait_result
+                    .expect("Failed to wait for idle device when destroying DlssSuperResolution");
+                release_result.expect("Failed to destroy DlssSuperResolution feature");
+            }
         }
     }
 }
@@ -258,36 +593,113 @@ impl Drop for DlssSuperResolution {
 unsafe impl Send for DlssSuperResolution {}
 unsafe impl Sync for DlssSuperResolution {}
 
+/// Eval params for a new context. The context keeps them for its lifetime, and only patches the per-frame fields when rendering.
+fn eval_params_template() -> NVSDK_NGX_VK_DLSS_Eval_Params {
+    NVSDK_NGX_VK_DLSS_Eval_Params {
+        Feature: NVSDK_NGX_VK_Feature_Eval_Params {
+            pInColor: ptr::null_mut(),
+            pInOutput: ptr::null_mut(),
+            InSharpness: 0.0,
+        },
+        pInDepth: ptr::null_mut(),
+        pInMotionVectors: ptr::null_mut(),
+        InJitterOffsetX: 0.0,
+        InJitterOffsetY: 0.0,
+        InRenderSubrectDimensions: NVSDK_NGX_Dimensions {
+            Width: 0,
+            Height: 0,
+        },
+        InReset: 0,
+        InMVScaleX: 1.0,
+        InMVScaleY: 1.0,
+        pInTransparencyMask: ptr::null_mut(),
+        pInExposureTexture: ptr::null_mut(),
+        pInBiasCurrentColorMask: ptr::null_mut(),
+        InColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InDepthSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InMVSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InTranslucencySubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InBiasCurrentColorSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InOutputSubrectBase: NVSDK_NGX_Coordinates { X: 0, Y: 0 },
+        InPreExposure: 0.0,
+        InExposureScale: 0.0,
+        InIndicatorInvertXAxis: 0,
+        InIndicatorInvertYAxis: 0,
+        GBufferSurface: NVSDK_NGX_VK_GBuffer {
+            pInAttrib: [ptr::null_mut(); 16],
+        },
+        InToneMapperType: NVSDK_NGX_ToneMapperType_NVSDK_NGX_TONEMAPPER_STRING,
+        pInMotionVectors3D: ptr::null_mut(),
+        pInIsParticleMask: ptr::null_mut(),
+        pInAnimatedTextureMask: ptr::null_mut(),
+        pInDepthHighRes: ptr::null_mut(),
+        pInPositionViewSpace: ptr::null_mut(),
+        InFrameTimeDeltaInMsec: 0.0,
+        pInRayTracingHitDistance: ptr::null_mut(),
+        pInMotionVectorsReflections: ptr::null_mut(),
+    }
+}
+
+/// Settings a [`DlssSuperResolution`] context is created with, for use as a cache key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DlssSuperResolutionSettings {
+    pub upscaled_resolution: [u32; 2],
+    pub perf_quality_mode: DlssPerfQualityMode,
+    pub feature_flags: DlssFeatureFlags,
+}
+
+impl DlssSuperResolutionSettings {
+    /// A hash of the settings that is stable across platforms and runs, unlike [`std::hash::DefaultHasher`].
+    pub fn settings_hash(&self) -> u64 {
+        stable_hash(&[
+            self.upscaled_resolution[0] as u64,
+            self.upscaled_resolution[1] as u64,
+            perf_quality_mode_key(self.perf_quality_mode),
+            self.feature_flags.bits() as u64,
+        ])
+    }
+}
+
 /// Inputs and output resources needed for rendering [`DlssSuperResolution`].
-pub struct DlssSuperResolutionRenderParameters<'a> {
+///
+/// Textures are [`TextureView`]s for [`DlssSuperResolution::render`], and [`VulkanTexture`]s for [`DlssSuperResolution::render_raw`].
+pub struct DlssSuperResolutionRenderParameters<'a, T = TextureView> {
     /// Main color view of your camera.
-    pub color: &'a TextureView,
+    pub color: &'a T,
     /// Depth buffer.
-    pub depth: &'a TextureView,
+    pub depth: &'a T,
     /// Motion vectors.
-    pub motion_vectors: &'a TextureView,
+    ///
+    /// Must be at the render resolution if [`DlssFeatureFlags::LowResolutionMotionVectors`] is set, and otherwise at the upscaled resolution.
+    pub motion_vectors: &'a T,
     /// Camera exposure settings.
-    pub exposure: DlssSuperResolutionExposure<'a>,
+    pub exposure: DlssSuperResolutionExposure<'a, T>,
     /// Optional per-pixel bias to make DLSS more reactive.
-    pub bias: Option<&'a TextureView>,
-    /// The texture DLSS outputs to.
-    pub dlss_output: &'a TextureView,
+    pub bias: Option<&'a T>,
+    /// The texture DLSS outputs to. Must not have an sRGB format.
+    pub dlss_output: &'a T,
     /// Whether DLSS should reset temporal history, useful for camera cuts.
     pub reset: bool,
     /// Subpixel jitter that was applied to your camera.
     pub jitter_offset: [f32; 2],
     /// Optionally use only a specific subrect of the input textures, rather than the whole textures.
+    ///
+    /// Defaults to [`DlssSuperResolution::render_resolution`].
     // TODO: Allow configuring partial texture origins
     pub partial_texture_size: Option<[u32; 2]>,
+    /// Optionally write to a subrect of [`Self::dlss_output`] starting at this origin, rather than writing to the whole texture.
+    ///
+    /// Requires [`DlssFeatureFlags::OutputSubrect`].
+    pub output_subrect_base: Option<[u32; 2]>,
     /// Optional scaling factor to apply to the values contained within [`Self::motion_vectors`].
     pub motion_vector_scale: Option<[f32; 2]>,
 }
 
 /// Camera exposure as input for [`DlssSuperResolution`]..
-pub enum DlssSuperResolutionExposure<'a> {
+pub enum DlssSuperResolutionExposure<'a, T = TextureView> {
     /// Exposure controlled by the application.
     Manual {
-        exposure: &'a TextureView,
+        exposure: &'a T,
         exposure_scale: Option<f32>,
         pre_exposure: Option<f32>,
     },
@@ -295,39 +707,78 @@ pub enum DlssSuperResolutionExposure<'a> {
     Automatic,
 }
 
-impl<'a> DlssSuperResolutionRenderParameters<'a> {
-    fn validate(&self) -> Result<(), DlssError> {
-        // TODO
-        Ok(())
-    }
-
-    fn barrier_list(&self) -> impl Iterator<Item = TextureTransition<&'a Texture>> {
-        fn resource_barrier(texture_view: &TextureView) -> TextureTransition<&Texture> {
-            TextureTransition {
-                texture: texture_view.texture(),
-                selector: None,
-                state: TextureUses::RESOURCE,
-            }
+impl<'a, T> DlssSuperResolutionRenderParameters<'a, T> {
+    fn validate(
+        &self,
+        feature_flags: DlssFeatureFlags,
+        upscaled_resolution: [u32; 2],
+        is_srgb: impl Fn(&T) -> bool,
+        size: impl Fn(&T) -> [u32; 2],
+    ) -> Result<(), DlssError> {
+        // TODO: Validate the remaining parameters
+        if is_srgb(self.dlss_output) {
+            return Err(DlssError::SrgbOutput);
         }
+        check_motion_vectors(
+            feature_flags,
+            upscaled_resolution,
+            size(self.color),
+            size(self.motion_vectors),
+            self.motion_vector_scale,
+        )
+    }
 
-        [
-            Some(resource_barrier(self.color)),
-            Some(resource_barrier(self.depth)),
-            Some(resource_barrier(self.motion_vectors)),
-            match &self.exposure {
+    fn ngx_resources(
+        &self,
+        to_ngx: impl Fn(DlssPassRole, &T) -> NVSDK_NGX_Resource_VK,
+    ) -> DlssSuperResolutionResources {
+        DlssSuperResolutionResources {
+            color: to_ngx(DlssPassRole::Color, self.color),
+            depth: to_ngx(DlssPassRole::Depth, self.depth),
+            motion_vectors: to_ngx(DlssPassRole::MotionVectors, self.motion_vectors),
+            exposure: match &self.exposure {
                 DlssSuperResolutionExposure::Manual { exposure, .. } => {
-                    Some(resource_barrier(exposure))
+                    Some(to_ngx(DlssPassRole::Exposure, exposure))
                 }
                 DlssSuperResolutionExposure::Automatic => None,
             },
-            self.bias.map(resource_barrier),
-            Some(TextureTransition {
-                texture: self.dlss_output.texture(),
-                selector: None,
-                state: TextureUses::STORAGE_READ_WRITE,
-            }),
-        ]
-        .into_iter()
-        .flatten()
+            bias: self.bias.map(|bias| to_ngx(DlssPassRole::Bias, bias)),
+            dlss_output: to_ngx(DlssPassRole::Output, self.dlss_output),
+        }
     }
 }
+
+impl<'a> DlssSuperResolutionRenderParameters<'a> {
+    /// The textures this evaluation reads and writes, for declaring its dependencies in a render graph.
+    pub fn pass_descriptor(&self) -> DlssPassDescriptor<'a> {
+        DlssPassDescriptor::new(
+            [
+                Some((DlssPassRole::Color, self.color.texture())),
+                Some((DlssPassRole::Depth, self.depth.texture())),
+                Some((DlssPassRole::MotionVectors, self.motion_vectors.texture())),
+                match &self.exposure {
+                    DlssSuperResolutionExposure::Manual { exposure, .. } => {
+                        Some((DlssPassRole::Exposure, exposure.texture()))
+                    }
+                    DlssSuperResolutionExposure::Automatic => None,
+                },
+                self.bias.map(|bias| (DlssPassRole::Bias, bias.texture())),
+            ],
+            self.dlss_output.texture(),
+        )
+    }
+
+    pub(crate) fn barrier_list(&self) -> impl Iterator<Item = TextureTransition<&'a Texture>> {
+        self.pass_descriptor().transitions()
+    }
+}
+
+/// NGX resources referenced by [`NVSDK_NGX_VK_DLSS_Eval_Params`], kept alive for the duration of evaluation.
+struct DlssSuperResolutionResources {
+    color: NVSDK_NGX_Resource_VK,
+    depth: NVSDK_NGX_Resource_VK,
+    motion_vectors: NVSDK_NGX_Resource_VK,
+    exposure: Option<NVSDK_NGX_Resource_VK>,
+    bias: Option<NVSDK_NGX_Resource_VK>,
+    dlss_output: NVSDK_NGX_Resource_VK,
+}
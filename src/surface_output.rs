@@ -0,0 +1,97 @@
+use crate::{
+    DlssError, DlssFeatureFlags, DlssPerfQualityMode, DlssSdk, DlssSuperResolutionResizer,
+    super_resolution::DlssSuperResolution,
+};
+use std::sync::{Arc, Mutex};
+use wgpu::{
+    Device, Extent3d, Queue, SurfaceConfiguration, Texture, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureView,
+};
+
+/// Ties a DLSS Super Resolution context and its output texture to the size of a surface.
+///
+/// Call [`Self::update`] whenever the surface is configured, or once per frame.
+pub struct DlssSurfaceOutput {
+    resizer: DlssSuperResolutionResizer,
+    output_format: TextureFormat,
+    output: Option<(Texture, TextureView)>,
+}
+
+impl DlssSurfaceOutput {
+    /// Create a new [`DlssSurfaceOutput`], whose output texture will use `output_format`.
+    ///
+    /// The output texture is created with [`TextureUsages::STORAGE_BINDING`], [`TextureUsages::TEXTURE_BINDING`], and [`TextureUsages::COPY_SRC`],
+    /// so it must be copied or blitted to the surface texture, which typically does not support storage usage.
+    pub fn new(
+        output_format: TextureFormat,
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        sdk: Arc<Mutex<DlssSdk>>,
+    ) -> Self {
+        Self {
+            resizer: DlssSuperResolutionResizer::new(perf_quality_mode, feature_flags, sdk),
+            output_format,
+            output: None,
+        }
+    }
+
+    /// Resize the context and output texture to match `surface_configuration`, if its size changed.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn update(
+        &mut self,
+        surface_configuration: &SurfaceConfiguration,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<&mut DlssSuperResolution, DlssError> {
+        let upscaled_resolution = [
+            surface_configuration.width.max(1),
+            surface_configuration.height.max(1),
+        ];
+
+        let output_size = Extent3d {
+            width: upscaled_resolution[0],
+            height: upscaled_resolution[1],
+            depth_or_array_layers: 1,
+        };
+        if self
+            .output
+            .as_ref()
+            .is_none_or(|(texture, _)| texture.size() != output_size)
+        {
+            let texture = device.create_texture(&TextureDescriptor {
+                label: Some("dlss_output"),
+                size: output_size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: self.output_format,
+                usage: TextureUsages::STORAGE_BINDING
+                    | TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&Default::default());
+            self.output = Some((texture, view));
+        }
+
+        self.resizer.update(upscaled_resolution, device, queue)
+    }
+
+    /// The resolution the camera should render at, pre-upscaling, or `None` if [`Self::update`] has not been called.
+    pub fn render_resolution(&self) -> Option<[u32; 2]> {
+        self.resizer
+            .context()
+            .map(|context| context.render_resolution())
+    }
+
+    /// The texture DLSS outputs to, or `None` if [`Self::update`] has not been called.
+    pub fn output_texture(&self) -> Option<&Texture> {
+        self.output.as_ref().map(|(texture, _)| texture)
+    }
+
+    /// A view of [`Self::output_texture`], for use as [`crate::super_resolution::DlssSuperResolutionRenderParameters::dlss_output`].
+    pub fn output_view(&self) -> Option<&TextureView> {
+        self.output.as_ref().map(|(_, view)| view)
+    }
+}
@@ -0,0 +1,112 @@
+use wgpu::{
+    Device, Extent3d, Queue, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureUsages,
+    util::{DeviceExt, TextureDataOrder},
+};
+
+/// Size of each checkerboard cell, in render resolution pixels.
+const CHECKER_SIZE: f32 = 16.0;
+/// Distance the checkerboard scrolls each frame, in render resolution pixels.
+const SCROLL_SPEED: [f32; 2] = [2.0, 1.0];
+
+/// Procedurally generated DLSS input textures: a diagonally scrolling checkerboard lit by a gradient.
+///
+/// Useful for exercising DLSS integrations, tests, and benchmarks without a full renderer.
+pub struct SyntheticInputs {
+    /// Lit scene color, in [`TextureFormat::Rgba8Unorm`].
+    pub color: Texture,
+    /// Depth gradient from near at the top to far at the bottom, in [`TextureFormat::R32Float`].
+    pub depth: Texture,
+    /// Motion vectors in render resolution pixels, pointing from the current frame to the previous frame,
+    /// in [`TextureFormat::Rg32Float`].
+    pub motion_vectors: Texture,
+    /// Unlit checkerboard color, in [`TextureFormat::Rgba8Unorm`].
+    pub albedo: Texture,
+}
+
+impl SyntheticInputs {
+    /// Generate the inputs for `frame_number`, rendered with the given subpixel `jitter_offset`.
+    ///
+    /// Textures are created with [`TextureUsages::TEXTURE_BINDING`], [`TextureUsages::COPY_SRC`], and [`TextureUsages::COPY_DST`].
+    pub fn new(
+        device: &Device,
+        queue: &Queue,
+        resolution: [u32; 2],
+        frame_number: u32,
+        jitter_offset: [f32; 2],
+    ) -> Self {
+        let [width, height] = resolution;
+        let pixel_count = (width * height) as usize;
+        let mut color = Vec::with_capacity(pixel_count * 4);
+        let mut depth = Vec::with_capacity(pixel_count * 4);
+        let mut motion_vectors = Vec::with_capacity(pixel_count * 8);
+        let mut albedo = Vec::with_capacity(pixel_count * 4);
+
+        for y in 0..height {
+            for x in 0..width {
+                let depth_value = (y as f32 + 0.5 + jitter_offset[1]) / height as f32;
+                let light_x = (x as f32 + 0.5 + jitter_offset[0]) / width as f32;
+                let scene_x =
+                    x as f32 + 0.5 + jitter_offset[0] + frame_number as f32 * SCROLL_SPEED[0];
+                let scene_y =
+                    y as f32 + 0.5 + jitter_offset[1] + frame_number as f32 * SCROLL_SPEED[1];
+                let checker = ((scene_x / CHECKER_SIZE).floor() + (scene_y / CHECKER_SIZE).floor())
+                    .rem_euclid(2.0);
+
+                let base = if checker == 0.0 {
+                    [0.9, 0.2, 0.1]
+                } else {
+                    [0.1, 0.3, 0.9]
+                };
+                let light = 0.25 + 0.75 * light_x;
+
+                albedo.extend(base.map(unorm8));
+                albedo.push(255);
+                color.extend(base.map(|channel| unorm8(channel * light)));
+                color.push(255);
+                depth.extend(depth_value.to_ne_bytes());
+                motion_vectors.extend((-SCROLL_SPEED[0]).to_ne_bytes());
+                motion_vectors.extend((-SCROLL_SPEED[1]).to_ne_bytes());
+            }
+        }
+
+        let create_texture = |label, format, data: &[u8]| {
+            device.create_texture_with_data(
+                queue,
+                &TextureDescriptor {
+                    label: Some(label),
+                    size: Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::TEXTURE_BINDING
+                        | TextureUsages::COPY_SRC
+                        | TextureUsages::COPY_DST,
+                    view_formats: &[],
+                },
+                TextureDataOrder::LayerMajor,
+                data,
+            )
+        };
+
+        Self {
+            color: create_texture("synthetic_color", TextureFormat::Rgba8Unorm, &color),
+            depth: create_texture("synthetic_depth", TextureFormat::R32Float, &depth),
+            motion_vectors: create_texture(
+                "synthetic_motion_vectors",
+                TextureFormat::Rg32Float,
+                &motion_vectors,
+            ),
+            albedo: create_texture("synthetic_albedo", TextureFormat::Rgba8Unorm, &albedo),
+        }
+    }
+}
+
+fn unorm8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
@@ -0,0 +1,364 @@
+use crate::{
+    DlssError, DlssFeatureFlags, DlssPerfQualityMode, DlssSdk, FeatureSupport,
+    mock::{MockSuperResolution, MockSuperResolutionRenderParameters},
+    ray_reconstruction::{
+        DlssRayReconstruction, DlssRayReconstructionDepthMode,
+        DlssRayReconstructionRenderParameters, DlssRayReconstructionRoughnessMode,
+        DlssRayReconstructionSpecularGuide,
+    },
+    super_resolution::{
+        DlssSuperResolution, DlssSuperResolutionExposure, DlssSuperResolutionRenderParameters,
+    },
+};
+use std::sync::{Arc, Mutex};
+use wgpu::{
+    Adapter, CommandBuffer, CommandEncoder, CommandEncoderDescriptor, Device, Queue, TextureView,
+};
+
+/// Upscaler that uses DLSS Ray Reconstruction if requested and supported, otherwise DLSS Super Resolution if supported,
+/// otherwise [`MockSuperResolution`], behind a single render entry point.
+pub enum DlssUpscaler {
+    /// DLSS Ray Reconstruction.
+    RayReconstruction(DlssRayReconstruction),
+    /// DLSS Super Resolution.
+    SuperResolution(DlssSuperResolution),
+    /// Bilinear upscaling, used when DLSS is unavailable.
+    Fallback {
+        upscaler: MockSuperResolution,
+        /// Used to create a command buffer for each render, matching DLSS.
+        device: Device,
+    },
+}
+
+/// Inputs and output resources needed for rendering [`DlssUpscaler`].
+///
+/// The fields have the same meaning as in [`DlssSuperResolutionRenderParameters`].
+pub struct DlssUpscalerRenderParameters<'a> {
+    pub color: &'a TextureView,
+    pub depth: &'a TextureView,
+    pub motion_vectors: &'a TextureView,
+    /// Camera exposure settings. Unused by DLSS Ray Reconstruction.
+    pub exposure: DlssSuperResolutionExposure<'a>,
+    pub bias: Option<&'a TextureView>,
+    pub dlss_output: &'a TextureView,
+    pub reset: bool,
+    pub jitter_offset: [f32; 2],
+    pub partial_texture_size: Option<[u32; 2]>,
+    pub motion_vector_scale: Option<[f32; 2]>,
+    /// Additional inputs for DLSS Ray Reconstruction.
+    ///
+    /// Must be provided if the upscaler is [`DlssUpscaler::RayReconstruction`], and is otherwise ignored.
+    pub ray_reconstruction: Option<DlssUpscalerRayReconstructionInputs<'a>>,
+}
+
+/// Additional inputs needed by [`DlssUpscaler::RayReconstruction`].
+///
+/// The fields have the same meaning as in [`DlssRayReconstructionRenderParameters`].
+pub struct DlssUpscalerRayReconstructionInputs<'a> {
+    pub diffuse_albedo: &'a TextureView,
+    pub specular_albedo: &'a TextureView,
+    pub normals: &'a TextureView,
+    pub roughness: Option<&'a TextureView>,
+    pub specular_guide: DlssRayReconstructionSpecularGuide<'a>,
+    pub screen_space_subsurface_scattering_guide: Option<&'a TextureView>,
+}
+
+impl DlssUpscaler {
+    /// Create the best upscaler available.
+    ///
+    /// DLSS Ray Reconstruction is only attempted if `ray_reconstruction` modes are provided.
+    /// If creating a DLSS context fails, or `sdk` is `None`, the next upscaler is used instead.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        upscaled_resolution: [u32; 2],
+        perf_quality_mode: DlssPerfQualityMode,
+        feature_flags: DlssFeatureFlags,
+        ray_reconstruction: Option<(
+            DlssRayReconstructionRoughnessMode,
+            DlssRayReconstructionDepthMode,
+        )>,
+        feature_support: &FeatureSupport,
+        sdk: Option<Arc<Mutex<DlssSdk>>>,
+        device: &Device,
+        queue: &Queue,
+    ) -> Self {
+        if let Some(sdk) = sdk {
+            if let Some((roughness_mode, depth_mode)) = ray_reconstruction
+                && feature_support.ray_reconstruction_supported
+                && let Ok(context) = DlssRayReconstruction::new(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    roughness_mode,
+                    depth_mode,
+                    Arc::clone(&sdk),
+                    device,
+                    queue,
+                )
+            {
+                return Self::RayReconstruction(context);
+            }
+
+            if feature_support.super_resolution_supported
+                && let Ok(context) = DlssSuperResolution::new(
+                    upscaled_resolution,
+                    perf_quality_mode,
+                    feature_flags,
+                    sdk,
+                    device,
+                    queue,
+                )
+            {
+                return Self::SuperResolution(context);
+            }
+        }
+
+        Self::Fallback {
+            upscaler: MockSuperResolution::new(upscaled_resolution, perf_quality_mode, device),
+            device: device.clone(),
+        }
+    }
+
+    /// Encode rendering commands for the upscaler.
+    ///
+    /// The resulting command buffer should be submitted following the same rules as [`DlssSuperResolution::render`].
+    ///
+    /// Returns [`DlssError::MissingInput`] if using DLSS Ray Reconstruction and [`DlssUpscalerRenderParameters::ray_reconstruction`] is `None`.
+    pub fn render(
+        &mut self,
+        render_parameters: DlssUpscalerRenderParameters,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        match self {
+            Self::RayReconstruction(context) => {
+                let inputs = render_parameters
+                    .ray_reconstruction
+                    .ok_or(DlssError::MissingInput)?;
+                context.render(
+                    DlssRayReconstructionRenderParameters {
+                        diffuse_albedo: inputs.diffuse_albedo,
+                        specular_albedo: inputs.specular_albedo,
+                        normals: inputs.normals,
+                        roughness: inputs.roughness,
+                        color: render_parameters.color,
+                        depth: render_parameters.depth,
+                        motion_vectors: render_parameters.motion_vectors,
+                        specular_guide: inputs.specular_guide,
+                        screen_space_subsurface_scattering_guide: inputs
+                            .screen_space_subsurface_scattering_guide,
+                        bias: render_parameters.bias,
+                        dlss_output: render_parameters.dlss_output,
+                        reset: render_parameters.reset,
+                        jitter_offset: render_parameters.jitter_offset,
+                        partial_texture_size: render_parameters.partial_texture_size,
+                        output_subrect_base: None,
+                        motion_vector_scale: render_parameters.motion_vector_scale,
+                    },
+                    command_encoder,
+                    adapter,
+                )
+            }
+            Self::SuperResolution(context) => context.render(
+                DlssSuperResolutionRenderParameters {
+                    color: render_parameters.color,
+                    depth: render_parameters.depth,
+                    motion_vectors: render_parameters.motion_vectors,
+                    exposure: render_parameters.exposure,
+                    bias: render_parameters.bias,
+                    dlss_output: render_parameters.dlss_output,
+                    reset: render_parameters.reset,
+                    jitter_offset: render_parameters.jitter_offset,
+                    partial_texture_size: render_parameters.partial_texture_size,
+                    output_subrect_base: None,
+                    motion_vector_scale: render_parameters.motion_vector_scale,
+                },
+                command_encoder,
+                adapter,
+            ),
+            Self::Fallback { upscaler, device } => {
+                let mut fallback_command_encoder =
+                    device.create_command_encoder(&CommandEncoderDescriptor {
+                        label: Some("dlss_upscaler_fallback"),
+                    });
+                upscaler.render(
+                    MockSuperResolutionRenderParameters {
+                        color: render_parameters.color,
+                        dlss_output: render_parameters.dlss_output,
+                        jitter_offset: render_parameters.jitter_offset,
+                        partial_texture_size: render_parameters.partial_texture_size,
+                    },
+                    &mut fallback_command_encoder,
+                );
+                Ok(fallback_command_encoder.finish())
+            }
+        }
+    }
+
+    /// Suggested subpixel camera jitter for a given frame.
+    pub fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
+        match self {
+            Self::RayReconstruction(context) => {
+                context.suggested_jitter(frame_number, render_resolution)
+            }
+            Self::SuperResolution(context) => {
+                context.suggested_jitter(frame_number, render_resolution)
+            }
+            Self::Fallback { upscaler, .. } => {
+                upscaler.suggested_jitter(frame_number, render_resolution)
+            }
+        }
+    }
+
+    /// Offset the sequence returned by [`Self::suggested_jitter`] by `offset` phases.
+    ///
+    /// See [`DlssSuperResolution::set_jitter_phase_offset`].
+    pub fn set_jitter_phase_offset(&mut self, offset: u32) {
+        match self {
+            Self::RayReconstruction(context) => context.set_jitter_phase_offset(offset),
+            Self::SuperResolution(context) => context.set_jitter_phase_offset(offset),
+            Self::Fallback { upscaler, .. } => upscaler.set_jitter_phase_offset(offset),
+        }
+    }
+
+    /// Suggested mip bias to apply when sampling textures.
+    pub fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
+        match self {
+            Self::RayReconstruction(context) => context.suggested_mip_bias(render_resolution),
+            Self::SuperResolution(context) => context.suggested_mip_bias(render_resolution),
+            Self::Fallback { upscaler, .. } => upscaler.suggested_mip_bias(render_resolution),
+        }
+    }
+
+    /// The upscaled resolution the upscaler will output at.
+    pub fn upscaled_resolution(&self) -> [u32; 2] {
+        match self {
+            Self::RayReconstruction(context) => context.upscaled_resolution(),
+            Self::SuperResolution(context) => context.upscaled_resolution(),
+            Self::Fallback { upscaler, .. } => upscaler.upscaled_resolution(),
+        }
+    }
+
+    /// The resolution the camera should render at, pre-upscaling.
+    pub fn render_resolution(&self) -> [u32; 2] {
+        match self {
+            Self::RayReconstruction(context) => context.render_resolution(),
+            Self::SuperResolution(context) => context.render_resolution(),
+            Self::Fallback { upscaler, .. } => upscaler.render_resolution(),
+        }
+    }
+}
+
+/// Common interface of [`DlssSuperResolution`], [`DlssRayReconstruction`], and [`DlssUpscaler`],
+/// for engine code that is generic over the context type.
+pub trait DlssTemporalUpscaler {
+    /// The inputs and output resources needed for rendering.
+    type RenderParameters<'a>;
+
+    /// Encode rendering commands. See [`DlssSuperResolution::render`].
+    fn render(
+        &mut self,
+        render_parameters: Self::RenderParameters<'_>,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError>;
+
+    /// Suggested subpixel camera jitter for a given frame.
+    fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2];
+
+    /// Suggested mip bias to apply when sampling textures.
+    fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32;
+
+    /// The upscaled resolution the upscaler will output at.
+    fn upscaled_resolution(&self) -> [u32; 2];
+
+    /// The resolution the camera should render at, pre-upscaling.
+    fn render_resolution(&self) -> [u32; 2];
+}
+
+impl DlssTemporalUpscaler for DlssSuperResolution {
+    type RenderParameters<'a> = DlssSuperResolutionRenderParameters<'a>;
+
+    fn render(
+        &mut self,
+        render_parameters: Self::RenderParameters<'_>,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        DlssSuperResolution::render(self, render_parameters, command_encoder, adapter)
+    }
+
+    fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
+        DlssSuperResolution::suggested_jitter(self, frame_number, render_resolution)
+    }
+
+    fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
+        DlssSuperResolution::suggested_mip_bias(self, render_resolution)
+    }
+
+    fn upscaled_resolution(&self) -> [u32; 2] {
+        DlssSuperResolution::upscaled_resolution(self)
+    }
+
+    fn render_resolution(&self) -> [u32; 2] {
+        DlssSuperResolution::render_resolution(self)
+    }
+}
+
+impl DlssTemporalUpscaler for DlssRayReconstruction {
+    type RenderParameters<'a> = DlssRayReconstructionRenderParameters<'a>;
+
+    fn render(
+        &mut self,
+        render_parameters: Self::RenderParameters<'_>,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        DlssRayReconstruction::render(self, render_parameters, command_encoder, adapter)
+    }
+
+    fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
+        DlssRayReconstruction::suggested_jitter(self, frame_number, render_resolution)
+    }
+
+    fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
+        DlssRayReconstruction::suggested_mip_bias(self, render_resolution)
+    }
+
+    fn upscaled_resolution(&self) -> [u32; 2] {
+        DlssRayReconstruction::upscaled_resolution(self)
+    }
+
+    fn render_resolution(&self) -> [u32; 2] {
+        DlssRayReconstruction::render_resolution(self)
+    }
+}
+
+impl DlssTemporalUpscaler for DlssUpscaler {
+    type RenderParameters<'a> = DlssUpscalerRenderParameters<'a>;
+
+    fn render(
+        &mut self,
+        render_parameters: Self::RenderParameters<'_>,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        DlssUpscaler::render(self, render_parameters, command_encoder, adapter)
+    }
+
+    fn suggested_jitter(&self, frame_number: u32, render_resolution: [u32; 2]) -> [f32; 2] {
+        DlssUpscaler::suggested_jitter(self, frame_number, render_resolution)
+    }
+
+    fn suggested_mip_bias(&self, render_resolution: [u32; 2]) -> f32 {
+        DlssUpscaler::suggested_mip_bias(self, render_resolution)
+    }
+
+    fn upscaled_resolution(&self) -> [u32; 2] {
+        DlssUpscaler::upscaled_resolution(self)
+    }
+
+    fn render_resolution(&self) -> [u32; 2] {
+        DlssUpscaler::render_resolution(self)
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{
+    DlssContextManager, DlssError, DlssInitOptions, DlssSdk,
+    super_resolution::{
+        DlssSuperResolutionExposure, DlssSuperResolutionRenderParameters,
+        DlssSuperResolutionSettings,
+    },
+};
+use std::{
+    hash::Hash,
+    iter,
+    sync::mpsc::{Sender, channel},
+    thread::{self, JoinHandle},
+};
+use wgpu::{Adapter, CommandBuffer, CommandEncoder, Device, Queue, TextureView};
+
+/// Runs every NGX call on a single dedicated thread, which owns the [`DlssSdk`] and all DLSS Super Resolution contexts.
+///
+/// DLSS objects never leave the worker thread, so their thread safety does not need to be relied upon.
+/// Each call blocks until the worker thread has processed it.
+///
+/// `K` identifies a camera, e.g. an entity ID. Contexts are cached per camera as with [`DlssContextManager`].
+pub struct DlssWorker<K: Send + 'static> {
+    sender: Option<Sender<Command<K>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Information about a DLSS Super Resolution context owned by a [`DlssWorker`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DlssWorkerContextInfo {
+    /// See [`crate::super_resolution::DlssSuperResolution::upscaled_resolution`].
+    pub upscaled_resolution: [u32; 2],
+    /// See [`crate::super_resolution::DlssSuperResolution::render_resolution`].
+    pub render_resolution: [u32; 2],
+    /// See [`crate::super_resolution::DlssSuperResolution::suggested_jitter_phase_count`], for the render resolution.
+    /// Use with [`crate::jitter::jitter_offset`].
+    pub jitter_phase_count: u32,
+}
+
+enum Command<K> {
+    SuperResolution {
+        camera: K,
+        settings: DlssSuperResolutionSettings,
+        reply: Sender<Result<DlssWorkerContextInfo, DlssError>>,
+    },
+    RenderSuperResolution {
+        camera: K,
+        settings: DlssSuperResolutionSettings,
+        render_parameters: Box<OwnedRenderParameters>,
+        adapter: Adapter,
+        reply: Sender<Result<CommandBuffer, DlssError>>,
+    },
+    EvictUnused,
+    Remove(K),
+}
+
+impl<K: Hash + Eq + Send + 'static> DlssWorker<K> {
+    /// Spawn the worker thread, and create the [`DlssSdk`] on it. See [`DlssSdk::new`].
+    pub fn new(options: DlssInitOptions, device: Device, queue: Queue) -> Result<Self, DlssError> {
+        let (sender, receiver) = channel::<Command<K>>();
+        let (init_sender, init_receiver) = channel();
+
+        let thread = thread::Builder::new()
+            .name("dlss_worker".to_owned())
+            .spawn(move || {
+                let mut context_manager = match DlssSdk::new(&options, device.clone()) {
+                    Ok(sdk) => {
+                        let _ = init_sender.send(Ok(()));
+                        DlssContextManager::new(sdk)
+                    }
+                    Err(err) => {
+                        let _ = init_sender.send(Err(err));
+                        return;
+                    }
+                };
+
+                for command in receiver {
+                    match command {
+                        Command::SuperResolution {
+                            camera,
+                            settings,
+                            reply,
+                        } => {
+                            let result = context_manager
+                                .super_resolution(
+                                    camera,
+                                    settings.upscaled_resolution,
+                                    settings.perf_quality_mode,
+                                    settings.feature_flags,
+                                    &device,
+                                    &queue,
+                                )
+                                .map(|context| DlssWorkerContextInfo {
+                                    upscaled_resolution: context.upscaled_resolution(),
+                                    render_resolution: context.render_resolution(),
+                                    jitter_phase_count: context
+                                        .suggested_jitter_phase_count(context.render_resolution()),
+                                });
+                            let _ = reply.send(result);
+                        }
+                        Command::RenderSuperResolution {
+                            camera,
+                            settings,
+                            render_parameters,
+                            adapter,
+                            reply,
+                        } => {
+                            let result = context_manager
+                                .super_resolution(
+                                    camera,
+                                    settings.upscaled_resolution,
+                                    settings.perf_quality_mode,
+                                    settings.feature_flags,
+                                    &device,
+                                    &queue,
+                                )
+                                .and_then(|context| {
+                                    render_parameters.with_borrowed(|render_parameters| {
+                                        context
+                                            .render_without_transitions(render_parameters, &adapter)
+                                    })
+                                });
+                            let _ = reply.send(result);
+                        }
+                        Command::EvictUnused => context_manager.evict_unused(),
+                        Command::Remove(camera) => context_manager.remove(&camera),
+                    }
+                }
+            })
+            .map_err(|_| DlssError::PlatformError)?;
+
+        init_receiver.recv().map_err(|_| DlssError::SdkPoisoned)??;
+
+        Ok(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    /// Get information about the context for `camera`, creating it if it does not exist or if the settings have changed.
+    ///
+    /// This should only be called if [`crate::FeatureSupport::super_resolution_supported`] is true.
+    pub fn super_resolution(
+        &self,
+        camera: K,
+        settings: DlssSuperResolutionSettings,
+    ) -> Result<DlssWorkerContextInfo, DlssError> {
+        let (reply, result) = channel();
+        self.send(Command::SuperResolution {
+            camera,
+            settings,
+            reply,
+        })?;
+        result.recv().map_err(|_| DlssError::SdkPoisoned)?
+    }
+
+    /// Encode rendering commands for the context for `camera`, creating it if needed as in [`Self::super_resolution`].
+    ///
+    /// Resource transitions are recorded into `command_encoder` on the calling thread.
+    /// The resulting command buffer should be submitted following the same rules as [`crate::super_resolution::DlssSuperResolution::render`].
+    pub fn render_super_resolution(
+        &self,
+        camera: K,
+        settings: DlssSuperResolutionSettings,
+        render_parameters: DlssSuperResolutionRenderParameters,
+        command_encoder: &mut CommandEncoder,
+        adapter: &Adapter,
+    ) -> Result<CommandBuffer, DlssError> {
+        command_encoder.transition_resources(iter::empty(), render_parameters.barrier_list());
+
+        let (reply, result) = channel();
+        self.send(Command::RenderSuperResolution {
+            camera,
+            settings,
+            render_parameters: Box::new(OwnedRenderParameters::new(&render_parameters)),
+            adapter: adapter.clone(),
+            reply,
+        })?;
+        result.recv().map_err(|_| DlssError::SdkPoisoned)?
+    }
+
+    /// See [`DlssContextManager::evict_unused`].
+    pub fn evict_unused(&self) -> Result<(), DlssError> {
+        self.send(Command::EvictUnused)
+    }
+
+    /// See [`DlssContextManager::remove`].
+    pub fn remove(&self, camera: K) -> Result<(), DlssError> {
+        self.send(Command::Remove(camera))
+    }
+
+    /// Returns [`DlssError::SdkPoisoned`] if the worker thread panicked.
+    fn send(&self, command: Command<K>) -> Result<(), DlssError> {
+        self.sender
+            .as_ref()
+            .unwrap()
+            .send(command)
+            .map_err(|_| DlssError::SdkPoisoned)
+    }
+}
+
+impl<K: Send + 'static> Drop for DlssWorker<K> {
+    fn drop(&mut self) {
+        // Closing the channel stops the worker thread, which then drops the contexts and SDK
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// [`DlssSuperResolutionRenderParameters`] with owned texture views, for sending to the worker thread.
+struct OwnedRenderParameters {
+    color: TextureView,
+    depth: TextureView,
+    motion_vectors: TextureView,
+    exposure: Option<(TextureView, Option<f32>, Option<f32>)>,
+    bias: Option<TextureView>,
+    dlss_output: TextureView,
+    reset: bool,
+    jitter_offset: [f32; 2],
+    partial_texture_size: Option<[u32; 2]>,
+    output_subrect_base: Option<[u32; 2]>,
+    motion_vector_scale: Option<[f32; 2]>,
+}
+
+impl OwnedRenderParameters {
+    fn new(render_parameters: &DlssSuperResolutionRenderParameters) -> Self {
+        Self {
+            color: render_parameters.color.clone(),
+            depth: render_parameters.depth.clone(),
+            motion_vectors: render_parameters.motion_vectors.clone(),
+            exposure: match &render_parameters.exposure {
+                DlssSuperResolutionExposure::Manual {
+                    exposure,
+                    exposure_scale,
+                    pre_exposure,
+                } => Some(((*exposure).clone(), *exposure_scale, *pre_exposure)),
+                DlssSuperResolutionExposure::Automatic => None,
+            },
+            bias: render_parameters.bias.cloned(),
+            dlss_output: render_parameters.dlss_output.clone(),
+            reset: render_parameters.reset,
+            jitter_offset: render_parameters.jitter_offset,
+            partial_texture_size: render_parameters.partial_texture_size,
+            output_subrect_base: render_parameters.output_subrect_base,
+            motion_vector_scale: render_parameters.motion_vector_scale,
+        }
+    }
+
+    fn with_borrowed<T>(&self, f: impl FnOnce(DlssSuperResolutionRenderParameters) -> T) -> T {
+        f(DlssSuperResolutionRenderParameters {
+            color: &self.color,
+            depth: &self.depth,
+            motion_vectors: &self.motion_vectors,
+            exposure: match &self.exposure {
+                Some((exposure, exposure_scale, pre_exposure)) => {
+                    DlssSuperResolutionExposure::Manual {
+                        exposure,
+                        exposure_scale: *exposure_scale,
+                        pre_exposure: *pre_exposure,
+                    }
+                }
+                None => DlssSuperResolutionExposure::Automatic,
+            },
+            bias: self.bias.as_ref(),
+            dlss_output: &self.dlss_output,
+            reset: self.reset,
+            jitter_offset: self.jitter_offset,
+            partial_texture_size: self.partial_texture_size,
+            output_subrect_base: self.output_subrect_base,
+            motion_vector_scale: self.motion_vector_scale,
+        })
+    }
+}